@@ -0,0 +1,82 @@
+//! Push/pop workloads shared by the `alloc_system` and `alloc_jemalloc`
+//! benches, which differ only in their global allocator.
+
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Instant;
+
+use conc_linklists_std_arc::list::List;
+use conc_linklists_std_arc::pool::{self, PoolStats};
+
+const PUSH_POPS: u64 = 50_000;
+const BURST: u64 = 1_000;
+
+type Work = fn(&List<u64>, usize);
+
+/// Pushes and pops one element at a time.
+fn push_pop(list: &List<u64>, t: usize) {
+    for i in 0..PUSH_POPS {
+        list.push_front(t as u64 * PUSH_POPS + i);
+        list.pop_front().unwrap();
+    }
+}
+
+/// Pushes `BURST` elements, then pops as many, so that more cells are
+/// freed at once than a small pool keeps.
+fn bursts(list: &List<u64>, t: usize) {
+    for _ in 0..PUSH_POPS / BURST {
+        for i in 0..BURST {
+            list.push_front(t as u64 * BURST + i);
+        }
+        for _ in 0..BURST {
+            list.pop_front().unwrap();
+        }
+    }
+}
+
+/// Runs `work` on `threads` threads, each with a pool of `capacity` cells,
+/// and returns the time per operation and the summed pool counters.
+fn timed(threads: usize, capacity: usize, work: Work) -> (f64, PoolStats) {
+    let list: Arc<List<u64>> = Arc::new(List::new());
+    let barrier = Arc::new(Barrier::new(threads + 1));
+    let jhs: Vec<_> = (0..threads)
+        .map(|t| {
+            let list = Arc::clone(&list);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                pool::set_capacity(capacity);
+                barrier.wait();
+                work(&list, t);
+                pool::stats()
+            })
+        })
+        .collect();
+    // the workers are parked on the barrier until it is passed here
+    let start = Instant::now();
+    barrier.wait();
+    let mut total = PoolStats::default();
+    for jh in jhs {
+        let stats = jh.join().unwrap();
+        total.hits += stats.hits;
+        total.misses += stats.misses;
+        total.recycled += stats.recycled;
+        total.discarded += stats.discarded;
+    }
+    let ops = threads as u64 * PUSH_POPS;
+    (start.elapsed().as_nanos() as f64 / ops as f64, total)
+}
+
+pub fn run(allocator: &str) {
+    let workloads: [(&str, Work); 2] = [("push/pop", push_pop), ("bursts", bursts)];
+    for (name, work) in workloads {
+        for threads in [1, 2, 4, 8] {
+            for capacity in [0, 64, 4 * BURST as usize] {
+                let (ns, stats) = timed(threads, capacity, work);
+                println!(
+                    "{allocator:8} {name:8} {threads} threads, pool {capacity:4}: {ns:8.2} ns/op, {:5.1}% hits",
+                    stats.hit_rate() * 100.0
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+//! Cell pool against jemalloc alone; see `alloc_system` for the same
+//! workloads on the system allocator.
+//!
+//! Run with `cargo bench --bench alloc_jemalloc`.
+
+use jemallocator::Jemalloc;
+
+#[global_allocator]
+static GLOBAL: Jemalloc = Jemalloc;
+
+#[path = "alloc/workload.rs"]
+mod workload;
+
+fn main() {
+    workload::run("jemalloc");
+}
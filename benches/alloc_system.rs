@@ -0,0 +1,11 @@
+//! Cell pool against the system allocator alone; see `alloc_jemalloc` for
+//! the same workloads on jemalloc.
+//!
+//! Run with `cargo bench --bench alloc_system`.
+
+#[path = "alloc/workload.rs"]
+mod workload;
+
+fn main() {
+    workload::run("system");
+}
@@ -0,0 +1,91 @@
+//! Compares the reclamation strategies of `List` on the same workloads:
+//! read-only traversals, which only the strategy makes contend, and a
+//! push/pop mix.
+//!
+//! Run with `cargo bench --bench reclaim`.
+
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use conc_linklists_std_arc::list::List;
+use conc_linklists_std_arc::reclaim::{Counted, Epoch, Hazard, Reclaim};
+
+const LEN: u64 = 1_000;
+const SEARCHES: u64 = 200;
+const PUSH_POPS: u64 = 20_000;
+
+/// Runs `work` on `threads` threads at once and returns the wall time.
+fn timed<R>(list: &Arc<List<u64, R>>, threads: usize, work: fn(&List<u64, R>, usize)) -> Duration
+where
+    R: Reclaim<u64> + 'static,
+    List<u64, R>: Send + Sync,
+{
+    let barrier = Arc::new(Barrier::new(threads + 1));
+    let jhs: Vec<_> = (0..threads)
+        .map(|t| {
+            let list = Arc::clone(list);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                barrier.wait();
+                work(&list, t);
+            })
+        })
+        .collect();
+    // the workers are parked on the barrier until it is passed here
+    let start = Instant::now();
+    barrier.wait();
+    for jh in jhs {
+        jh.join().unwrap();
+    }
+    start.elapsed()
+}
+
+/// Walks to elements spread over the list with a cursor, without taking
+/// any element out.
+fn search<R: Reclaim<u64>>(list: &List<u64, R>, t: usize) {
+    for i in 0..SEARCHES {
+        let needle = (i * 7 + t as u64) % LEN;
+        let mut cursor = list.first().unwrap();
+        cursor.advance_while(|&val| val != needle).unwrap();
+        assert_eq!(cursor.val().as_deref(), Some(&needle));
+    }
+}
+
+fn push_pop<R: Reclaim<u64>>(list: &List<u64, R>, t: usize) {
+    for i in 0..PUSH_POPS {
+        list.push_front(t as u64 * PUSH_POPS + i);
+        list.pop_front().unwrap();
+    }
+}
+
+fn bench<R>(name: &str)
+where
+    R: Reclaim<u64> + 'static,
+    List<u64, R>: Send + Sync,
+{
+    for threads in [1, 2, 4, 8] {
+        let list: Arc<List<u64, R>> = Arc::new((0..LEN).collect());
+        let elapsed = timed(&list, threads, search::<R>);
+        let hops = threads as u64 * SEARCHES * LEN / 2;
+        println!(
+            "{name:8} search    {threads} threads: {:8.2} ns/hop",
+            elapsed.as_nanos() as f64 / hops as f64
+        );
+    }
+    for threads in [1, 2, 4, 8] {
+        let list: Arc<List<u64, R>> = Arc::new((0..LEN).collect());
+        let elapsed = timed(&list, threads, push_pop::<R>);
+        let ops = threads as u64 * PUSH_POPS;
+        println!(
+            "{name:8} push/pop  {threads} threads: {:8.2} ns/op",
+            elapsed.as_nanos() as f64 / ops as f64
+        );
+    }
+}
+
+fn main() {
+    bench::<Counted>("Counted");
+    bench::<Epoch>("Epoch");
+    bench::<Hazard>("Hazard");
+}
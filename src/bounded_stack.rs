@@ -0,0 +1,147 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::Full;
+use crate::list::{Iter, List};
+use crate::wait::Waiters;
+
+/// Lock-free stack on top of [`List`] holding at most `capacity` values.
+///
+/// The length is tracked atomically: a push reserves its slot before
+/// linking the value and a pop releases it after unlinking, so the stack
+/// never holds more than `capacity` values, while [`BoundedStack::len`]
+/// may count pushes still in progress.
+#[derive(Debug)]
+pub struct BoundedStack<T> {
+    list: List<T>,
+    capacity: usize,
+    len: AtomicUsize,
+    /// Pushers waiting for space.
+    space: Waiters,
+}
+
+impl<T> BoundedStack<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            list: List::new(),
+            capacity,
+            len: AtomicUsize::new(0),
+            space: Waiters::new(),
+        }
+    }
+
+    /// Iterates over the values from the top of the stack; the iteration
+    /// is weakly consistent, see [`Iter`].
+    pub fn iter(&self) -> Iter<T> {
+        self.list.iter()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::SeqCst)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Pushes `value` onto the stack, or hands it back if the stack is
+    /// full.
+    pub fn try_push(&self, value: T) -> Result<(), Full<T>> {
+        let reserved = self
+            .len
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |len| {
+                (len < self.capacity).then_some(len + 1)
+            });
+        if reserved.is_err() {
+            return Err(Full(value));
+        }
+        self.list.push_front(value);
+        Ok(())
+    }
+
+    /// Pushes `value` onto the stack, parking the thread while it is full.
+    pub fn push(&self, value: T) {
+        let mut value = value;
+        loop {
+            match self.try_push(value) {
+                Ok(()) => return,
+                Err(Full(rejected)) => {
+                    value = rejected;
+                    self.space
+                        .wait_while(|| self.len.load(Ordering::SeqCst) >= self.capacity);
+                }
+            }
+        }
+    }
+
+    /// Removes the value on top of the stack; see [`List::pop_front`].
+    pub fn pop(&self) -> Option<T> {
+        let value = self.list.pop_front()?;
+        self.len.fetch_sub(1, Ordering::SeqCst);
+        self.space.notify_all();
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use super::BoundedStack;
+    use crate::error::Full;
+
+    #[test]
+    fn test_try_push() {
+        let stack = BoundedStack::new(2);
+        stack.try_push("a".to_string()).unwrap();
+        stack.try_push("b".to_string()).unwrap();
+
+        let Full(rejected) = stack.try_push("c".to_string()).unwrap_err();
+        assert_eq!(rejected, "c");
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.iter().map(|val| (*val).clone()).collect::<Vec<_>>(), ["b", "a"]);
+
+        assert_eq!(stack.pop().as_deref(), Some("b"));
+        stack.try_push(rejected).unwrap();
+        assert_eq!(stack.pop().as_deref(), Some("c"));
+        assert_eq!(stack.pop().as_deref(), Some("a"));
+        assert_eq!(stack.pop().as_deref(), None);
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn test_blocking_push() {
+        let stack: Arc<BoundedStack<usize>> = Arc::new(BoundedStack::new(4));
+
+        const NUM_THREADS: usize = 4;
+        const ITER: usize = 500;
+
+        let mut producers = vec![];
+        for t in 0..NUM_THREADS {
+            let stack_copy = Arc::clone(&stack);
+            producers.push(thread::spawn(move || {
+                for i in 0..ITER {
+                    stack_copy.push(t * ITER + i);
+                    assert!(stack_copy.len() <= stack_copy.capacity());
+                }
+            }));
+        }
+
+        let mut popped = vec![];
+        while popped.len() < NUM_THREADS * ITER {
+            match stack.pop() {
+                Some(val) => popped.push(val),
+                None => thread::yield_now(),
+            }
+        }
+        for jh in producers {
+            jh.join().unwrap();
+        }
+        popped.sort_unstable();
+        assert_eq!(popped, (0..NUM_THREADS * ITER).collect::<Vec<_>>());
+        assert!(stack.is_empty());
+    }
+}
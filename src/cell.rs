@@ -1,101 +1,469 @@
+use std::cell::UnsafeCell;
+use std::fmt::Debug;
+use std::marker::PhantomData;
 use std::mem::ManuallyDrop;
+use std::ops::Deref;
 use std::ptr::{self};
 use std::sync::Weak;
 use std::sync::atomic::Ordering;
-use std::sync::{atomic::AtomicPtr, Arc};
+use std::sync::{
+    atomic::{AtomicBool, AtomicIsize, AtomicPtr, AtomicUsize},
+    Arc,
+};
+
+use crate::error::{ListError, Result};
+use crate::hazard;
+use crate::node::{self, Node, State, Unwrapped};
+use crate::pool;
+
+/// A link word: a pointer from `Arc::into_raw`, with the number of readers
+/// which loaded it and may not hold their own reference yet in the bits
+/// above [`PTR_BITS`].
+///
+/// A reader takes its own reference, then takes its count back out of the
+/// word, or, if the word was swapped out, out of the [`Readers`] of the
+/// value, where the writer which swapped it adds the count it found. Counts
+/// of one value are interchangeable, so a link may point at a value again
+/// after it was swung away from it, and nobody waits for anyone.
+///
+/// The lowest bit, [`FROZEN`], makes every swing of the link fail, see
+/// [`Cell::freeze_next`].
+const PTR_BITS: u32 = 48;
+const READER: usize = 1 << PTR_BITS;
+const FROZEN: usize = 1;
+const PTR_MASK: usize = (READER - 1) & !FROZEN;
+
+fn word_ptr<X>(word: usize) -> *mut X {
+    (word & PTR_MASK) as *mut X
+}
+
+fn ptr_word<X>(ptr: *const X) -> usize {
+    assert!(ptr as usize & !PTR_MASK == 0, "address does not fit a link word");
+    ptr as usize
+}
+
+/// Readers of swapped out link words minus the readers which left them;
+/// a reference to the value is held while it is not zero.
+#[derive(Debug, Default)]
+pub struct Readers(AtomicIsize);
+
+/// A value linked through link words.
+trait Shared: Sized {
+    fn readers(&self) -> &Readers;
+
+    /// Adds `count` readers to those of the swapped out links; `this` is the
+    /// reference of one of these links, or of a reader of one.
+    fn add_readers(this: &Arc<Self>, count: isize) {
+        let prev = this.readers().0.fetch_add(count, Ordering::SeqCst);
+        let ptr = Arc::as_ptr(this);
+        // SAFETY: `this` keeps the value alive, and the reference held while
+        // the count is not zero is only dropped by whoever brings it to zero
+        if prev == 0 {
+            unsafe { Arc::increment_strong_count(ptr) };
+        } else if prev + count == 0 {
+            unsafe { Arc::decrement_strong_count(ptr) };
+        }
+    }
+
+    /// A reference to the value `link` points at.
+    fn load(link: &AtomicUsize) -> Option<Arc<Self>> {
+        let ptr = word_ptr::<Self>(link.fetch_add(READER, Ordering::SeqCst));
+        let this = (!ptr.is_null()).then(|| {
+            // SAFETY: the link keeps the value alive until the word is
+            // swapped out, and then the count of this reader does
+            unsafe {
+                Arc::increment_strong_count(ptr);
+                Arc::from_raw(ptr)
+            }
+        });
+        let mut word = link.load(Ordering::SeqCst);
+        loop {
+            if word_ptr::<Self>(word) != ptr || word >> PTR_BITS == 0 {
+                // the word this reader entered was swapped out; any reader
+                // still counted in the current one takes its place
+                if let Some(this) = &this {
+                    Self::add_readers(this, -1);
+                }
+                return this;
+            }
+            match link.compare_exchange_weak(word, word - READER, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return this,
+                Err(actual) => word = actual,
+            }
+        }
+    }
 
-use anyhow::{Result, anyhow};
+    /// The reference held by the swapped out link word `word`.
+    fn swapped(word: usize) -> Option<Arc<Self>> {
+        let ptr = word_ptr::<Self>(word);
+        if ptr.is_null() {
+            return None;
+        }
+        // SAFETY: link words hold pointers from `Arc::into_raw`
+        let this = unsafe { Arc::from_raw(ptr) };
+        let count = word >> PTR_BITS;
+        if count != 0 {
+            Self::add_readers(&this, count as isize);
+        }
+        Some(this)
+    }
+}
 
-// #[derive(Debug)]
-pub struct Links<T: Debug> {
-    next: AtomicPtr<Cell<T>>,
+#[derive(Debug)]
+pub struct Links<T> {
+    /// Link word of the next cell.
+    next: AtomicUsize,
     back_link: AtomicPtr<Cell<T>>,
+    readers: Readers,
+    /// Set on the cells of lists with the `Hazard` strategy, whose readers
+    /// may use the cell `next` points at without holding a reference.
+    hazard: AtomicBool,
 }
 
-pub enum Dummy<T: Debug> {
+/// Value of a data cell.
+///
+/// Readers borrow it for as long as they use it, and the one which removed
+/// the cell moves it out once it is not borrowed; cursors and traversals
+/// holding the cell never keep it from being moved out.
+pub struct Value<T, S: State = AtomicUsize> {
+    /// [`BORROW`] per borrow, plus [`TAKEN`] once the value is moved out.
+    state: S,
+    slot: UnsafeCell<ManuallyDrop<T>>,
+}
+
+const TAKEN: usize = 1;
+const BORROW: usize = 2;
+
+// SAFETY: the value is moved out by one thread, and only read otherwise
+unsafe impl<T: Send, S: State + Send> Send for Value<T, S> {}
+unsafe impl<T: Send + Sync, S: State + Sync> Sync for Value<T, S> {}
+
+impl State for AtomicUsize {
+    fn add(&self, count: usize) -> usize {
+        self.fetch_add(count, Ordering::SeqCst)
+    }
+
+    fn sub(&self, count: usize) {
+        self.fetch_sub(count, Ordering::Release);
+    }
+
+    fn replace(&self, current: usize, new: usize) -> bool {
+        self.compare_exchange(current, new, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    fn get_mut(&mut self) -> &mut usize {
+        AtomicUsize::get_mut(self)
+    }
+}
+
+impl<T, S: State> Value<T, S> {
+    pub(crate) fn new(value: T) -> Self {
+        Self {
+            state: S::default(),
+            slot: UnsafeCell::new(ManuallyDrop::new(value)),
+        }
+    }
+
+    /// Borrows the value, unless it was moved out.
+    pub(crate) fn borrow(&self) -> Option<Borrowed<'_, T, S>> {
+        self.acquire().then(|| Borrowed {
+            // SAFETY: the borrow keeps the value from being moved out
+            value: unsafe { self.get() },
+            state: &self.state,
+        })
+    }
+
+    /// Registers a borrow, unless the value was moved out.
+    pub(crate) fn acquire(&self) -> bool {
+        if self.state.add(BORROW) & TAKEN != 0 {
+            self.release();
+            return false;
+        }
+        true
+    }
+
+    pub(crate) fn release(&self) {
+        self.state.sub(BORROW);
+    }
+
+    /// # Safety
+    ///
+    /// The caller holds a borrow, or removed the cell and keeps the value
+    /// from being moved out.
+    pub(crate) unsafe fn get(&self) -> &T {
+        &*self.slot.get()
+    }
+
+    /// Moves the value out, unless it is borrowed.
+    ///
+    /// # Safety
+    ///
+    /// Only the one which removed the cell may move the value out.
+    pub(crate) unsafe fn try_take(&self) -> Option<T> {
+        if !self.state.replace(0, TAKEN) {
+            return None;
+        }
+        Some(ManuallyDrop::take(&mut *self.slot.get()))
+    }
+
+    /// The value, unless it was moved out.
+    pub(crate) fn into_inner(mut self) -> Option<T> {
+        let state = std::mem::replace(self.state.get_mut(), TAKEN);
+        // SAFETY: the state now tells `drop` that the value was moved out
+        (state & TAKEN == 0).then(|| unsafe { ManuallyDrop::take(self.slot.get_mut()) })
+    }
+}
+
+impl<T, S: State> Drop for Value<T, S> {
+    fn drop(&mut self) {
+        if *self.state.get_mut() & TAKEN == 0 {
+            // SAFETY: the value was not moved out
+            unsafe { ManuallyDrop::drop(self.slot.get_mut()) }
+        }
+    }
+}
+
+impl<T: Debug, S: State> Debug for Value<T, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.borrow() {
+            Some(value) => f.debug_tuple("Value").field(&*value).finish(),
+            None => f.write_str("Value(<taken>)"),
+        }
+    }
+}
+
+/// A borrow of the value of a data cell, see [`Value`].
+pub struct Borrowed<'a, T: ?Sized, S: State = AtomicUsize> {
+    value: &'a T,
+    state: &'a S,
+}
+
+impl<'a, T: ?Sized, S: State> Borrowed<'a, T, S> {
+    /// Borrows a part of the value.
+    pub fn map<U: ?Sized>(this: Self, f: impl FnOnce(&T) -> &U) -> Borrowed<'a, U, S> {
+        let this = ManuallyDrop::new(this);
+        Borrowed {
+            value: f(this.value),
+            state: this.state,
+        }
+    }
+}
+
+impl<T: ?Sized, S: State> Deref for Borrowed<'_, T, S> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: ?Sized, S: State> Drop for Borrowed<'_, T, S> {
+    fn drop(&mut self) {
+        self.state.sub(BORROW);
+    }
+}
+
+impl<T: ?Sized + Debug, S: State> Debug for Borrowed<'_, T, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+#[derive(Debug)]
+pub enum Dummy<T> {
     First(Links<T>),
-    Last,
+    Last(Readers),
 }
 
-// #[derive(Debug)]
-pub enum Cell<T: Debug> {
-    Data { links: Links<T>, data: T },
+#[derive(Debug)]
+pub enum Cell<T> {
+    Data { links: Links<T>, data: Value<T> },
     Aux { links: Links<T> },
     Dummy(Dummy<T>),
 }
 
-use std::fmt::Debug;
+impl<T> Links<T> {
+    fn new(next: *mut Cell<T>) -> Self {
+        Self {
+            next: AtomicUsize::new(ptr_word(next)),
+            back_link: AtomicPtr::default(),
+            readers: Readers::default(),
+            hazard: AtomicBool::new(false),
+        }
+    }
+
+    fn load_next(&self) -> *mut Cell<T> {
+        word_ptr(self.next.load(Ordering::SeqCst))
+    }
+
+    /// Replaces the word of `next` and returns the reference it held.
+    fn replace_next(&self, word: usize) -> Option<Arc<Cell<T>>> {
+        Cell::swapped(self.next.swap(word, Ordering::SeqCst))
+    }
 
+    fn take_next(&self) -> Option<Arc<Cell<T>>> {
+        self.replace_next(0)
+    }
+
+    /// Takes the reference of `next` out of a cell being freed, unless a
+    /// hazard pointer publishes its target, in which case it is retired.
+    fn release_next(&self) -> Option<Arc<Cell<T>>> {
+        let next = self.take_next()?;
+        // a reader which published `next` checks that it is still linked
+        // after publishing it, so it either fails or is seen here
+        if self.hazard.load(Ordering::Relaxed) && hazard::is_protected(Arc::as_ptr(&next)) {
+            hazard::retire(next);
+            return None;
+        }
+        Some(next)
+    }
+
+    /// Releases both links.
+    ///
+    /// Cells reachable through `next` which are owned by this link only are
+    /// unlinked one by one, instead of being freed by recursive `Arc` drops,
+    /// so that tearing down a chain of any length uses constant stack.
+    fn release(&self) {
+        let ptr = self.back_link.swap(ptr::null_mut(), Ordering::AcqRel);
+        if !ptr.is_null() {
+            ManuallyDrop::into_inner(Cell::_defrost_weak(ptr));
+        }
+
+        let mut next = self.release_next();
+        while let Some(cell) = next {
+            next = match pool::unwrap(cell) {
+                Ok(cell) => cell.links().and_then(Links::release_next),
+                Err(_shared) => None,
+            };
+        }
+    }
+}
 
-impl<T: Debug> Cell<T> {
-    pub fn drop_links(&self) {
-        // debug_assert!({
-        //     println!("dropping {:?}", self);
-        //     true
-        // });
+impl<T> Shared for Cell<T> {
+    fn readers(&self) -> &Readers {
         use self::Cell::*;
         use self::Dummy::*;
         match self {
-            Data { ref links, .. } | Aux { ref links } | Dummy(First(ref links)) => {
-                let ptr = links.next.load(Ordering::Acquire);
-                if ptr.is_null() {
-                    return;
-                }
-                let tmp = Cell::defrost(ptr);
-                ManuallyDrop::into_inner(tmp);
-                let ptr = links.back_link.load(Ordering::Acquire);
-                if ptr.is_null() {
-                    return;
-                }
-                let tmp = Cell::_defrost_weak(ptr);
-                ManuallyDrop::into_inner(tmp);
-            }
-            Dummy(Last) => {},
+            Data { ref links, .. } | Aux { ref links } | Dummy(First(ref links)) => &links.readers,
+            Dummy(Last(ref readers)) => readers,
         }
+    }
+}
 
+impl<T> Drop for Links<T> {
+    fn drop(&mut self) {
+        self.release();
     }
 }
 
+/// Weak reference to a cell stored in a [`WeakLink`].
+#[derive(Debug)]
+struct Hint<T> {
+    cell: Weak<Cell<T>>,
+    readers: Readers,
+}
 
+impl<T> Shared for Hint<T> {
+    fn readers(&self) -> &Readers {
+        &self.readers
+    }
+}
 
+/// Weak link to a cell kept outside of the chain, e.g. a position hint.
+#[derive(Debug)]
+pub struct WeakLink<T> {
+    /// Link word of the stored hint.
+    hint: AtomicUsize,
+    _hint: PhantomData<Arc<Hint<T>>>,
+}
+
+impl<T> WeakLink<T> {
+    fn replace(&self, word: usize) {
+        Hint::<T>::swapped(self.hint.swap(word, Ordering::SeqCst));
+    }
+}
 
-impl<T: Debug> Cell<T> {
+impl<T> node::Hint<Arc<Cell<T>>> for WeakLink<T> {
+    fn new() -> Self {
+        Self {
+            hint: AtomicUsize::new(0),
+            _hint: PhantomData,
+        }
+    }
 
-    pub fn new_aux(next: Arc<Cell<T>>) -> Arc<Cell<T>> {
+    fn store(&self, cell: &Arc<Cell<T>>) {
+        let hint = Arc::new(Hint {
+            cell: Arc::downgrade(cell),
+            readers: Readers::default(),
+        });
+        self.replace(ptr_word(Arc::into_raw(hint)));
+    }
+
+    fn clear(&self) {
+        self.replace(0);
+    }
+
+    fn upgrade(&self) -> Option<Arc<Cell<T>>> {
+        Hint::load(&self.hint)?.cell.upgrade()
+    }
+}
+
+impl<T> Drop for WeakLink<T> {
+    fn drop(&mut self) {
+        self.replace(0);
+    }
+}
+
+impl<T> Cell<T> {
+    fn links(&self) -> Option<&Links<T>> {
+        use self::Cell::*;
+        use self::Dummy::*;
+        match self {
+            Data { ref links, .. } | Aux { ref links } | Dummy(First(ref links)) => Some(links),
+            Dummy(Last(..)) => None,
+        }
+    }
+}
+
+impl<T> Cell<T> {
+
+    pub(crate) fn new_aux(next: Arc<Cell<T>>) -> Arc<Cell<T>> {
         let next = next.conserve();
         use self::Cell::*;
-        Arc::new(Aux {
-            links: Links {
-                next: AtomicPtr::new(next),
-                back_link: AtomicPtr::default(),
-            },
+        pool::alloc(Aux {
+            links: Links::new(next),
         })
     }
 
-    pub fn new_data(data: T, next: Arc<Cell<T>>) -> Arc<Cell<T>> {
+    pub(crate) fn new_data(data: T, next: Arc<Cell<T>>) -> Arc<Cell<T>> {
         let next = next.conserve();
         use self::Cell::*;
-        Arc::new(Data {
-            data,
-            links: Links {
-                next: AtomicPtr::new(next),
-                back_link: AtomicPtr::default(),
-            },
+        pool::alloc(Data {
+            data: Value::new(data),
+            links: Links::new(next),
         })
     }
 
-    pub fn new_last() -> Arc<Cell<T>> {
-        Arc::new(Cell::Dummy(Dummy::Last))
+    /// Data cell followed by its aux cell, with the aux not yet linked to
+    /// a successor.
+    pub(crate) fn new_detached(data: T) -> Arc<Cell<T>> {
+        use self::Cell::*;
+        let aux = pool::alloc(Aux {
+            links: Links::new(ptr::null_mut()),
+        });
+        Cell::new_data(data, aux)
+    }
+
+    pub(crate) fn new_last() -> Arc<Cell<T>> {
+        Arc::new(Cell::Dummy(Dummy::Last(Readers::default())))
     }
 
-    pub fn new_first(next: Arc<Cell<T>>) -> Arc<Cell<T>> {
+    pub(crate) fn new_first(next: Arc<Cell<T>>) -> Arc<Cell<T>> {
         let next = next.conserve();
         use self::Cell::*;
         use self::Dummy::*;
-        Arc::new(Dummy(First(Links {
-            next: AtomicPtr::new(next),
-            back_link: AtomicPtr::default(),
-        })))
+        Arc::new(Dummy(First(Links::new(next))))
     }
 
     pub fn is_last(&self) -> bool {
@@ -105,7 +473,7 @@ impl<T: Debug> Cell<T> {
             Data { .. } => false,
             Aux { .. } => false,
             Dummy(First(..)) => false,
-            Dummy(Last) => true,
+            Dummy(Last(..)) => true,
         }
     }
 
@@ -116,7 +484,7 @@ impl<T: Debug> Cell<T> {
             Data { .. } => true,
             Aux { .. } => false,
             Dummy(First(..)) => false,
-            Dummy(Last) => false,
+            Dummy(Last(..)) => false,
         }
     }
 
@@ -127,19 +495,45 @@ impl<T: Debug> Cell<T> {
             Data { .. } => true,
             Aux { .. } => false,
             Dummy(First(..)) => true,
-            Dummy(Last) => true,
+            Dummy(Last(..)) => true,
+        }
+    }
+
+
+    /// Unwraps a cell owned by the caller only into its data, if it is a
+    /// data cell, and the cell following it.
+    pub(crate) fn try_unwrap(this: Arc<Self>) -> std::result::Result<Unwrapped<T, Self>, Arc<Self>> {
+        let cell = pool::unwrap(this)?;
+        let next = cell.links().and_then(Links::take_next);
+        let data = match cell {
+            Cell::Data { data, .. } => data.into_inner(),
+            _ => None,
+        };
+        Ok((data, next))
+    }
+
+    /// Whether the data cell has been deleted from its list; deletion is
+    /// the only place where a back link is stored.
+    pub(crate) fn is_removed(&self) -> bool {
+        match self {
+            Cell::Data { ref links, .. } => !links.back_link.load(Ordering::Acquire).is_null(),
+            _ => false,
         }
     }
 
+    /// Borrows the value of a data cell, unless it was moved out.
+    pub fn val(&self) -> Option<Borrowed<'_, T>> {
+        self.value()?.borrow()
+    }
 
-    pub fn val(&self) -> Option<&T> {
+    pub(crate) fn value(&self) -> Option<&Value<T>> {
         use self::Cell::*;
         use self::Dummy::*;
         match self {
             Data { data , .. }  => {
                 Some(data)
             }
-            Dummy(Last) | Aux {..} | Dummy(First(..))=> None,
+            Dummy(Last(..)) | Aux {..} | Dummy(First(..))=> None,
         }
     }
 
@@ -150,32 +544,22 @@ impl<T: Debug> Cell<T> {
         ManuallyDrop::new(unsafe {Weak::from_raw(this)})
     }
 
-    pub fn conserve(self: Arc<Self>) -> *mut Self {
+    pub(crate) fn conserve(self: Arc<Self>) -> *mut Self {
         Arc::into_raw(self) as *mut Self
     }
 
-    fn defrost(this: *mut Self) -> ManuallyDrop<Arc<Self>> {
-        ManuallyDrop::new(unsafe { Arc::from_raw(this) })
-    }
 
-    pub fn next_dup(&self) -> Option<Arc<Cell<T>>> {
+    pub(crate) fn next_dup(&self) -> Option<Arc<Cell<T>>> {
         use self::Cell::*;
         use self::Dummy::*;
         match self {
             Data { ref links, .. } | Aux { ref links } | Dummy(First(ref links)) => {
-                let ptr = links.next.load(Ordering::Acquire);
-                if ptr.is_null() {
-                    return None;
-                }
-                let tmp = Cell::defrost(ptr);
-                let res = Arc::clone(&*tmp);
-
-                Some(res)
+                Cell::load(&links.next)
             }
-            Dummy(Last) => None,
+            Dummy(Last(..)) => None,
         }
     }
-    pub fn store_backlink(&self, backlink: Option<Weak<Self>>) {
+    pub(crate) fn store_backlink(&self, backlink: Option<Weak<Self>>) {
         use self::Cell::*;
         use self::Dummy::*;
         match self {
@@ -192,12 +576,12 @@ impl<T: Debug> Cell<T> {
                 }
                 let _dropped = ManuallyDrop::into_inner(Cell::_defrost_weak(prev)) ;
             }
-            Dummy(Last) |  Dummy(First(..)) | Aux { .. } => {},
+            Dummy(Last(..)) |  Dummy(First(..)) | Aux { .. } => {},
         }
 
     }
 
-    pub fn backlink_dup(&self) -> Option<Arc<Self>> {
+    pub(crate) fn backlink_dup(&self) -> Option<Arc<Self>> {
         use self::Cell::*;
         use self::Dummy::*;
         match self {
@@ -210,12 +594,12 @@ impl<T: Debug> Cell<T> {
                 let tmp = Cell::_defrost_weak(prev);
                 tmp.upgrade()
             }
-            Dummy(Last) |  Dummy(First(..)) | Aux { .. } => None,
+            Dummy(Last(..)) |  Dummy(First(..)) | Aux { .. } => None,
         }
 
     }
 
-    pub fn store_next(&self, next: Option<Arc<Cell<T>>>) {
+    pub(crate) fn store_next(&self, next: Option<Arc<Cell<T>>>) {
         use self::Cell::*;
         use self::Dummy::*;
         match self {
@@ -226,64 +610,217 @@ impl<T: Debug> Cell<T> {
                     Some(_n) => _n.conserve(),
 
                 };
-                let prev = links.next.swap(new, Ordering::AcqRel);
-                if prev.is_null() {
-                    return;
-                }
-                let _dropped = ManuallyDrop::into_inner(Cell::defrost(prev)) ;
+                let _dropped = links.replace_next(ptr_word(new));
             }
-            Dummy(Last) => {},
+            Dummy(Last(..)) => {},
         }
     }
 
+    /// Takes the reference of the `next` link out, leaving it null.
+    pub(crate) fn take_next(&self) -> Option<Arc<Cell<T>>> {
+        self.links()?.take_next()
+    }
 
-    pub fn swap_in_next(
-        &self,
-        p: Arc<Cell<T>>,
-        n: Option<Arc<Cell<T>>>,
-    ) -> Result<Arc<Cell<T>>> {
+
+    /// Swings the `next` link from `expected` to `n` with a single CAS and
+    /// returns the reference the link held to `expected`, which the caller
+    /// retires (see [`Reclaim`](crate::reclaim::Reclaim)).
+    pub(crate) fn swap_in_next(&self, expected: &Cell<T>, n: Arc<Cell<T>>) -> Result<Arc<Cell<T>>> {
         use self::Cell::*;
         use self::Dummy::*;
         match self {
             Data { ref links, .. } | Aux { ref links } | Dummy(First(ref links)) => {
-                let p_ptr = Arc::as_ptr(&p) as *mut Cell<T>;
-                let n_ptr = match n {
-                    None => ptr::null_mut(),
-                    Some(ref _n) => Arc::as_ptr(_n) as *mut Cell<T>,
+                let p_ptr = expected as *const Cell<T> as *mut Cell<T>;
+                let n_word = ptr_word(Arc::as_ptr(&n));
+
+                // readers registered on the word do not change its pointer
+                let mut word = links.next.load(Ordering::SeqCst);
+                loop {
+                    if word_ptr::<Cell<T>>(word) != p_ptr || word & FROZEN != 0 {
+                        return Err(ListError::CasFailed {
+                            actual: word & PTR_MASK,
+                            expected: p_ptr as usize,
+                        });
+                    }
+                    match links.next.compare_exchange_weak(word, n_word, Ordering::SeqCst, Ordering::SeqCst) {
+                        Ok(_) => break,
+                        Err(actual) => word = actual,
+                    }
+                }
 
-                };
+                n.conserve();
+                Ok(Cell::swapped(word).expect("the expected cell is not null"))
+            }
+            Dummy(Last(..)) => Err(ListError::CorruptedChain),
+        }
+    }
 
-                links
-                    .next
-                    .compare_exchange(p_ptr, n_ptr, Ordering::AcqRel, Ordering::Acquire)
-                    .map_err(|ptr| {
-                        anyhow!(
-                            "[err compare_exchange] actual {:p}, expected {:p}",
-                            ptr, p_ptr
-                        )
-                    })?;
-
-                drop(p);
-                match n {
-                    None => ptr::null_mut(),
-                    Some(_n) => _n.conserve(),
-                };
-                Ok(ManuallyDrop::into_inner(Cell::defrost(p_ptr)))
+    /// Freezes the `next` link while it points at `expected`, so that
+    /// [`Cell::swap_in_next`] fails on it until it is thawed; returns
+    /// whether it was frozen.
+    pub(crate) fn freeze_next(&self, expected: &Cell<T>) -> bool {
+        let Some(links) = self.links() else {
+            return false;
+        };
+        let mut word = links.next.load(Ordering::SeqCst);
+        loop {
+            if !ptr::eq(word_ptr(word), expected) || word & FROZEN != 0 {
+                return false;
+            }
+            match links.next.compare_exchange_weak(word, word | FROZEN, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return true,
+                Err(actual) => word = actual,
+            }
+        }
+    }
+
+    /// Thaws a link frozen by [`Cell::freeze_next`].
+    pub(crate) fn thaw_next(&self) {
+        if let Some(links) = self.links() {
+            links.next.fetch_and(!FROZEN, Ordering::SeqCst);
+        }
+    }
+
+    pub(crate) fn is_frozen(&self) -> bool {
+        self.links()
+            .is_some_and(|links| links.next.load(Ordering::SeqCst) & FROZEN != 0)
+    }
+
+    /// Raw `next` pointer, null for the last cell; it is only valid while
+    /// the link, or a pinned epoch, keeps the cell alive.
+    pub(crate) fn load_next(&self) -> *mut Cell<T> {
+        match self.links() {
+            Some(links) => links.load_next(),
+            None => ptr::null_mut(),
+        }
+    }
+
+    /// Raw `next` pointer, published with `publish` until `next` still
+    /// points at it afterwards; null for the last cell.
+    pub(crate) fn load_next_published(&self, mut publish: impl FnMut(*mut Cell<T>)) -> *mut Cell<T> {
+        let Some(links) = self.links() else {
+            return ptr::null_mut();
+        };
+        let mut ptr = links.load_next();
+        loop {
+            publish(ptr);
+            let again = links.load_next();
+            if again == ptr {
+                return ptr;
             }
-            Dummy(Last) => Err(anyhow!("no next for last variant")),
+            ptr = again;
         }
     }
 
-    pub fn next_cmp(&self, target: &Arc<Cell<T>>) -> bool {
+    /// Marks the cell as one of a list with the `Hazard` strategy, before it
+    /// is published.
+    pub(crate) fn mark_hazard(&self) {
+        if let Some(links) = self.links() {
+            links.hazard.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn next_cmp(&self, target: &Cell<T>) -> bool {
         use self::Cell::*;
         use self::Dummy::*;
         match self {
             Data { ref links, .. } | Aux { ref links } | Dummy(First(ref links)) => {
-                let ptr = links.next.load(Ordering::Acquire);
-                let target_ptr = Arc::as_ptr(target);
-                ptr as *const Cell<T> == target_ptr
+                let ptr = links.load_next();
+                ptr::eq(ptr, target)
             }
-            Dummy(Last) => false,
+            Dummy(Last(..)) => false,
         }
     }
 }
+
+impl<T> Node<T> for Cell<T> {
+    type Strong = Arc<Cell<T>>;
+    type State = AtomicUsize;
+    type Hint = WeakLink<T>;
+
+    fn new_detached(data: T) -> Arc<Cell<T>> {
+        Cell::new_detached(data)
+    }
+
+    fn new_aux(next: Arc<Cell<T>>) -> Arc<Cell<T>> {
+        Cell::new_aux(next)
+    }
+
+    fn new_first(next: Arc<Cell<T>>) -> Arc<Cell<T>> {
+        Cell::new_first(next)
+    }
+
+    fn new_last() -> Arc<Cell<T>> {
+        Cell::new_last()
+    }
+
+    fn is_last(&self) -> bool {
+        Cell::is_last(self)
+    }
+
+    fn is_data_cell(&self) -> bool {
+        Cell::is_data_cell(self)
+    }
+
+    fn is_normal_cell(&self) -> bool {
+        Cell::is_normal_cell(self)
+    }
+
+    fn is_removed(&self) -> bool {
+        Cell::is_removed(self)
+    }
+
+    fn value(&self) -> Option<&Value<T>> {
+        Cell::value(self)
+    }
+
+    fn next_dup(&self) -> Option<Arc<Cell<T>>> {
+        Cell::next_dup(self)
+    }
+
+    fn store_next(&self, next: Option<Arc<Cell<T>>>) {
+        Cell::store_next(self, next)
+    }
+
+    fn take_next(&self) -> Option<Arc<Cell<T>>> {
+        Cell::take_next(self)
+    }
+
+    fn next_cmp(&self, target: &Cell<T>) -> bool {
+        Cell::next_cmp(self, target)
+    }
+
+    fn swap_in_next(&self, expected: &Cell<T>, n: Arc<Cell<T>>) -> Result<Arc<Cell<T>>> {
+        Cell::swap_in_next(self, expected, n)
+    }
+
+    fn freeze_next(&self, expected: &Cell<T>) -> bool {
+        Cell::freeze_next(self, expected)
+    }
+
+    fn thaw_next(&self) {
+        Cell::thaw_next(self)
+    }
+
+    fn is_frozen(&self) -> bool {
+        Cell::is_frozen(self)
+    }
+
+    fn backlink_dup(&self) -> Option<Arc<Cell<T>>> {
+        Cell::backlink_dup(self)
+    }
+
+    fn set_backlink(&self, cell: &Arc<Cell<T>>) {
+        self.store_backlink(Some(Arc::downgrade(cell)));
+    }
+
+    fn try_unwrap(this: Arc<Cell<T>>) -> std::result::Result<Unwrapped<T, Cell<T>>, Arc<Cell<T>>> {
+        Cell::try_unwrap(this)
+    }
+
+    /// Keeps the allocation of the last reference in the cell pool, see
+    /// [`pool`](crate::pool).
+    fn recycle(this: Arc<Cell<T>>) {
+        pool::release(this);
+    }
+}
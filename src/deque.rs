@@ -0,0 +1,115 @@
+use crate::list::{Iter, List};
+
+/// Lock-free double-ended queue on top of [`List`].
+///
+/// Both ends insert with a single CAS and remove with the backlink-based
+/// delete of the list. Only the front is O(1) though: both back operations
+/// walk from hints left by the last ones, and [`Deque::pop_back`] walks
+/// the list once the element a hint preceded is gone. A work queue should
+/// let its owner work the front and thieves take from the back.
+#[derive(Debug)]
+pub struct Deque<T> {
+    list: List<T>,
+}
+
+impl<T> Default for Deque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deque<T> {
+    pub fn new() -> Self {
+        Self { list: List::new() }
+    }
+
+    /// Iterates over the values from the front to the back of the deque;
+    /// the iteration is weakly consistent, see [`Iter`].
+    pub fn iter(&self) -> Iter<T> {
+        self.list.iter()
+    }
+
+    pub fn push_front(&self, value: T) {
+        self.list.push_front(value);
+    }
+
+    pub fn push_back(&self, value: T) {
+        self.list.push_back(value);
+    }
+
+    /// See [`List::pop_front`].
+    pub fn pop_front(&self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    /// See [`List::pop_back`].
+    pub fn pop_back(&self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use super::Deque;
+
+    #[test]
+    fn test_both_ends() {
+        let deque = Deque::new();
+        deque.push_back(2);
+        deque.push_front(1);
+        deque.push_back(3);
+        deque.push_front(0);
+        assert_eq!(deque.iter().map(|val| *val).collect::<Vec<_>>(), [0, 1, 2, 3]);
+        assert_eq!(deque.pop_back(), Some(3));
+        assert_eq!(deque.pop_front(), Some(0));
+        assert_eq!(deque.pop_back(), Some(2));
+        assert_eq!(deque.pop_back(), Some(1));
+        assert_eq!(deque.pop_front(), None);
+        assert_eq!(deque.pop_back(), None);
+    }
+
+    #[test]
+    fn test_concurrent_deque() {
+        let deque: Arc<Deque<usize>> = Arc::new(Deque::new());
+
+        const NUM_THREADS: usize = 8;
+        const ITER: usize = 1000;
+
+        // owners push and pop at the front, thieves steal from the back
+        let mut jhs = vec![];
+        for t in 0..NUM_THREADS {
+            let deque_copy = Arc::clone(&deque);
+            jhs.push(thread::spawn(move || {
+                let mut taken = vec![];
+                for i in 0..ITER {
+                    deque_copy.push_front(t * ITER + i);
+                    if i % 3 == 0 {
+                        taken.extend(deque_copy.pop_front());
+                    }
+                }
+                taken
+            }));
+        }
+        for _ in 0..NUM_THREADS / 2 {
+            let deque_copy = Arc::clone(&deque);
+            jhs.push(thread::spawn(move || {
+                let mut stolen = vec![];
+                for _ in 0..ITER / 10 {
+                    stolen.extend(deque_copy.pop_back());
+                }
+                stolen
+            }));
+        }
+        let mut all: Vec<usize> = jhs
+            .into_iter()
+            .flat_map(|jh| jh.join().unwrap())
+            .collect();
+        while let Some(val) = deque.pop_front() {
+            all.push(val);
+        }
+        all.sort_unstable();
+        assert_eq!(all, (0..NUM_THREADS * ITER).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// Errors of the cell and cursor operations.
+///
+/// `NeedsUpdate` and `CasFailed` are reported under contention and are
+/// expected to be retried; `CorruptedChain` means a list invariant is broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListError {
+    /// The list was modified around the cursor since its last update.
+    NeedsUpdate,
+    /// Compare-and-swap of a `next` link found another cell than expected.
+    CasFailed { actual: usize, expected: usize },
+    /// The cursor is positioned at the end of the list.
+    AtEnd,
+    /// The cursor has no target.
+    InvalidCursor,
+    /// A link which is always set was found empty.
+    CorruptedChain,
+}
+
+pub type Result<T> = std::result::Result<T, ListError>;
+
+impl fmt::Display for ListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListError::NeedsUpdate => f.write_str("cursor needs updating"),
+            ListError::CasFailed { actual, expected } => write!(
+                f,
+                "compare_exchange failed: actual {:#x}, expected {:#x}",
+                actual, expected
+            ),
+            ListError::AtEnd => f.write_str("cursor is at the end of the list"),
+            ListError::InvalidCursor => f.write_str("cursor in invalid state: target is None"),
+            ListError::CorruptedChain => f.write_str("unexpected None in next"),
+        }
+    }
+}
+
+impl std::error::Error for ListError {}
+
+/// Error of a push into a full bounded container, handing the value back.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Full<T>(pub T);
+
+impl<T> Full<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for Full<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Full(..)")
+    }
+}
+
+impl<T> fmt::Display for Full<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("container is at capacity")
+    }
+}
+
+impl<T> std::error::Error for Full<T> {}
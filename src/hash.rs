@@ -0,0 +1,553 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::ops::Deref;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::cell::{Borrowed, Cell};
+use crate::error::ListError;
+use crate::list::{Cursor, List, Ref};
+
+/// Split-ordered lock-free hash map (Shalev and Shavit).
+///
+/// All entries live in one [`List`], sorted by the bit-reversed hash of
+/// their key. Bucket `b` is a sentinel cell inserted lazily in front of
+/// the entries whose hash ends with the bits of `b`, so growing the table
+/// never moves an entry: a new bucket just splits the run of its parent
+/// bucket by inserting one more sentinel.
+pub struct ConcurrentHashMap<K, V, S = RandomState> {
+    // owns the dummy cells around the chain; all access goes through
+    // the bucket sentinels
+    #[allow(dead_code)]
+    list: List<Node<K, V>>,
+    buckets: Directory<K, V>,
+    size: AtomicUsize,
+    count: AtomicUsize,
+    hasher: S,
+}
+
+#[derive(Debug)]
+enum Node<K, V> {
+    Sentinel(u64),
+    Entry { so_key: u64, key: K, value: V },
+}
+
+type NodeCell<K, V> = Arc<Cell<Node<K, V>>>;
+type Slot<K, V> = AtomicPtr<Cell<Node<K, V>>>;
+
+impl<K, V> Node<K, V> {
+    fn so_key(&self) -> u64 {
+        match self {
+            Node::Sentinel(so_key) | Node::Entry { so_key, .. } => *so_key,
+        }
+    }
+}
+
+/// Entries per bucket above which the table doubles its bucket count.
+const LOAD_FACTOR: usize = 2;
+/// Segment 0 holds bucket 0, segment `s > 0` holds buckets
+/// `2^(s-1)..2^s`.
+const SEGMENTS: usize = 33;
+const MAX_BUCKETS: usize = 1 << (SEGMENTS - 1);
+
+fn so_regular(hash: u64) -> u64 {
+    (hash | 1 << 63).reverse_bits()
+}
+
+fn so_sentinel(bucket: usize) -> u64 {
+    (bucket as u64).reverse_bits()
+}
+
+/// Bucket whose run is split by `bucket`.
+fn parent(bucket: usize) -> usize {
+    bucket & !(1 << (usize::BITS - 1 - bucket.leading_zeros()))
+}
+
+/// Growable bucket directory; segments are allocated on first use and
+/// each slot owns a reference to its sentinel cell.
+struct Directory<K, V> {
+    segments: [AtomicPtr<Slot<K, V>>; SEGMENTS],
+}
+
+impl<K, V> Directory<K, V> {
+    fn new() -> Self {
+        Self {
+            segments: std::array::from_fn(|_| AtomicPtr::default()),
+        }
+    }
+
+    fn segment_len(segment: usize) -> usize {
+        if segment == 0 {
+            1
+        } else {
+            1 << (segment - 1)
+        }
+    }
+
+    fn locate(bucket: usize) -> (usize, usize) {
+        if bucket == 0 {
+            return (0, 0);
+        }
+        let segment = (usize::BITS - bucket.leading_zeros()) as usize;
+        (segment, bucket - Self::segment_len(segment))
+    }
+
+    fn slot(&self, bucket: usize) -> &Slot<K, V> {
+        let (segment, index) = Self::locate(bucket);
+        let mut ptr = self.segments[segment].load(Ordering::Acquire);
+        if ptr.is_null() {
+            let fresh: Box<[Slot<K, V>]> = (0..Self::segment_len(segment))
+                .map(|_| AtomicPtr::default())
+                .collect();
+            let fresh = Box::into_raw(fresh) as *mut Slot<K, V>;
+            ptr = match self.segments[segment].compare_exchange(
+                ptr::null_mut(),
+                fresh,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => fresh,
+                Err(current) => {
+                    // SAFETY: `fresh` lost the race and was never published,
+                    // so this thread owns it; the segment dropped here is
+                    // not the `current` one handed out below
+                    drop(unsafe { Self::segment_box(fresh, segment) });
+                    current
+                }
+            };
+        }
+        // SAFETY: a published segment is never replaced, and is only freed
+        // by `Drop`, which takes `&mut self` and so outlives every `&Slot`
+        // borrowed from `&self`; `index < segment_len(segment)` by `locate`
+        unsafe { &*ptr.add(index) }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` comes from a boxed slice of `segment_len(segment)` slots, which
+    /// the caller owns and nobody accesses afterwards.
+    unsafe fn segment_box(
+        ptr: *mut Slot<K, V>,
+        segment: usize,
+    ) -> Box<[Slot<K, V>]> {
+        Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, Self::segment_len(segment)))
+    }
+
+    fn get(&self, bucket: usize) -> Option<NodeCell<K, V>> {
+        let ptr = self.slot(bucket).load(Ordering::Acquire);
+        if ptr.is_null() {
+            return None;
+        }
+        // SAFETY: `ptr` comes from `Arc::into_raw` in `set`, and a set slot
+        // is never cleared or overwritten, so the reference it owns keeps
+        // the cell alive for as long as `&self` is borrowed; the count taken
+        // here belongs to the returned `Arc`
+        unsafe {
+            Arc::increment_strong_count(ptr);
+            Some(Arc::from_raw(ptr))
+        }
+    }
+
+    /// Publishes `sentinel` for `bucket`, or returns the one published
+    /// concurrently.
+    fn set(&self, bucket: usize, sentinel: NodeCell<K, V>) -> NodeCell<K, V> {
+        let raw = Arc::into_raw(sentinel.clone()) as *mut Cell<Node<K, V>>;
+        match self.slot(bucket).compare_exchange(
+            ptr::null_mut(),
+            raw,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => sentinel,
+            Err(_) => {
+                // SAFETY: `raw` was not published, so the reference taken by
+                // `Arc::into_raw` above is still ours to drop
+                drop(unsafe { Arc::from_raw(raw) });
+                self.get(bucket).expect("slot has been set")
+            }
+        }
+    }
+}
+
+impl<K, V> Drop for Directory<K, V> {
+    fn drop(&mut self) {
+        for (segment, ptr) in self.segments.iter_mut().enumerate() {
+            let ptr = *ptr.get_mut();
+            if ptr.is_null() {
+                continue;
+            }
+            // SAFETY: published segments are boxed slices of their
+            // `segment_len`, and `&mut self` excludes any other access
+            let slots = unsafe { Self::segment_box(ptr, segment) };
+            for slot in slots.iter() {
+                let cell = slot.load(Ordering::Acquire);
+                if !cell.is_null() {
+                    // SAFETY: a set slot owns one reference from `set`
+                    drop(unsafe { Arc::from_raw(cell) });
+                }
+            }
+        }
+    }
+}
+
+/// Value of a [`ConcurrentHashMap`] entry, kept alive while the guard is
+/// held.
+#[derive(Debug)]
+pub struct EntryRef<K, V> {
+    node: Ref<Node<K, V>>,
+}
+
+impl<K, V> EntryRef<K, V> {
+    pub fn key(&self) -> &K {
+        match &*self.node {
+            Node::Entry { key, .. } => key,
+            Node::Sentinel(_) => unreachable!("refs point to entries"),
+        }
+    }
+}
+
+impl<K, V> Deref for EntryRef<K, V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        match &*self.node {
+            Node::Entry { value, .. } => value,
+            Node::Sentinel(_) => unreachable!("refs point to entries"),
+        }
+    }
+}
+
+impl<K, V, S> fmt::Debug for ConcurrentHashMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcurrentHashMap")
+            .field("len", &self.len())
+            .field("buckets", &self.size.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, V> Default for ConcurrentHashMap<K, V, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> ConcurrentHashMap<K, V, RandomState> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K, V, S> ConcurrentHashMap<K, V, S> {
+    pub fn with_hasher(hasher: S) -> Self {
+        let list = List::new();
+        let buckets = Directory::new();
+
+        let sentinel = Cell::new_detached(Node::Sentinel(so_sentinel(0)));
+        list.first()
+            .expect("list chain is corrupted")
+            .try_insert_cell(sentinel.clone())
+            .unwrap_or_else(|_| unreachable!("list is not shared yet"));
+        buckets.set(0, sentinel);
+
+        Self {
+            list,
+            buckets,
+            size: AtomicUsize::new(2),
+            count: AtomicUsize::new(0),
+            hasher,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn bucket(&self, bucket: usize) -> NodeCell<K, V> {
+        if let Some(sentinel) = self.buckets.get(bucket) {
+            return sentinel;
+        }
+        let parent = self.bucket(parent(bucket));
+        let so_key = so_sentinel(bucket);
+
+        let mut cursor: Cursor<_> = Cursor::after(&parent).expect("list chain is corrupted");
+        let mut cell = Cell::new_detached(Node::Sentinel(so_key));
+        let sentinel = loop {
+            cursor
+                .advance_while(|node| node.so_key() < so_key)
+                .expect("list chain is corrupted");
+            if let Some(target) = cursor.target_data() {
+                if target.val().is_some_and(|node| node.so_key() == so_key) {
+                    break target.clone();
+                }
+            }
+            match cursor.try_insert_cell(cell.clone()) {
+                Ok(()) => break cell,
+                Err(err) if err.error() == ListError::NeedsUpdate => {
+                    cell = err.into_cell();
+                    cursor.update().expect("list chain is corrupted");
+                }
+                Err(err) => panic!("list chain is corrupted: {}", err.error()),
+            }
+        };
+        self.buckets.set(bucket, sentinel)
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> ConcurrentHashMap<K, V, S> {
+    /// Cursor into the bucket of `key`, with `so_key` of `key`.
+    fn locate(&self, key: &K) -> (Cursor<Node<K, V>>, u64) {
+        let hash = self.hasher.hash_one(key);
+        let bucket = hash as usize & (self.size.load(Ordering::Acquire) - 1);
+        let cursor = Cursor::after(&self.bucket(bucket)).expect("list chain is corrupted");
+        (cursor, so_regular(hash))
+    }
+
+    /// Advances the cursor to the entry of `key`, or to the position where
+    /// it would be inserted.
+    fn find(cursor: &mut Cursor<Node<K, V>>, so_key: u64, key: &K) -> Option<NodeCell<K, V>> {
+        cursor
+            .advance_while(|node| node.so_key() < so_key)
+            .expect("list chain is corrupted");
+        // distinct keys may share a full hash
+        loop {
+            match cursor.val().as_deref() {
+                Some(Node::Entry { so_key: so, key: k, .. }) if *so == so_key => {
+                    if k == key {
+                        return cursor.target_data().cloned();
+                    }
+                }
+                _ => return None,
+            }
+            cursor.next().expect("list chain is corrupted");
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<EntryRef<K, V>> {
+        let (mut cursor, so_key) = self.locate(key);
+        Self::find(&mut cursor, so_key, key)
+            .and_then(Ref::new)
+            .map(|node| EntryRef { node })
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        let (mut cursor, so_key) = self.locate(key);
+        Self::find(&mut cursor, so_key, key).is_some()
+    }
+
+    /// Inserts `value` under `key` unless the key is present; returns
+    /// whether it was inserted.
+    pub fn insert(&self, key: K, value: V) -> bool {
+        let (mut cursor, so_key) = self.locate(&key);
+        fn key_of<K, V>(cell: &NodeCell<K, V>) -> Borrowed<'_, K> {
+            let node = cell.val().expect("detached cell is an entry");
+            Borrowed::map(node, |node| match node {
+                Node::Entry { key, .. } => key,
+                Node::Sentinel(_) => unreachable!("detached cell is an entry"),
+            })
+        }
+
+        let mut cell = Cell::new_detached(Node::Entry { so_key, key, value });
+        loop {
+            if Self::find(&mut cursor, so_key, &key_of(&cell)).is_some() {
+                return false;
+            }
+            match cursor.try_insert_cell(cell) {
+                Ok(()) => break,
+                Err(err) if err.error() == ListError::NeedsUpdate => {
+                    cell = err.into_cell();
+                    cursor.update().expect("list chain is corrupted");
+                }
+                Err(err) => panic!("list chain is corrupted: {}", err.error()),
+            }
+        }
+
+        let count = self.count.fetch_add(1, Ordering::Relaxed) + 1;
+        let size = self.size.load(Ordering::Relaxed);
+        if count > size * LOAD_FACTOR && size < MAX_BUCKETS {
+            let _ = self
+                .size
+                .compare_exchange(size, size * 2, Ordering::AcqRel, Ordering::Relaxed);
+        }
+        true
+    }
+
+    /// Removes the entry of `key` and returns it.
+    pub fn remove(&self, key: &K) -> Option<EntryRef<K, V>> {
+        let (mut cursor, so_key) = self.locate(key);
+        loop {
+            Self::find(&mut cursor, so_key, key)?;
+            match cursor.try_delete() {
+                Ok(removed) => {
+                    self.count.fetch_sub(1, Ordering::Relaxed);
+                    return Some(EntryRef {
+                        node: Ref::from(removed),
+                    });
+                }
+                Err(ListError::NeedsUpdate) => cursor.update().expect("list chain is corrupted"),
+                Err(err) => panic!("list chain is corrupted: {}", err),
+            }
+        }
+    }
+}
+
+/// Split-ordered lock-free hash set, a [`ConcurrentHashMap`] without values.
+#[derive(Debug)]
+pub struct ConcurrentHashSet<T, S = RandomState> {
+    map: ConcurrentHashMap<T, (), S>,
+}
+
+impl<T> Default for ConcurrentHashSet<T, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ConcurrentHashSet<T, RandomState> {
+    pub fn new() -> Self {
+        Self {
+            map: ConcurrentHashMap::new(),
+        }
+    }
+}
+
+impl<T, S> ConcurrentHashSet<T, S> {
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            map: ConcurrentHashMap::with_hasher(hasher),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<T: Hash + Eq, S: BuildHasher> ConcurrentHashSet<T, S> {
+    /// Inserts `value`; returns `false` if an equal element is present.
+    pub fn insert(&self, value: T) -> bool {
+        self.map.insert(value, ())
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.map.contains_key(value)
+    }
+
+    /// Removes the element equal to `value`; returns whether it was present.
+    pub fn remove(&self, value: &T) -> bool {
+        self.map.remove(value).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+    use std::{sync::Arc, thread};
+
+    use super::{ConcurrentHashMap, ConcurrentHashSet, Node};
+
+    fn so_keys<K, V>(map: &ConcurrentHashMap<K, V>) -> Vec<u64> {
+        let mut cursor = map.list.first().unwrap();
+        let mut so_keys = vec![];
+        while let Some(so_key) = cursor.val().map(|node| node.so_key()) {
+            so_keys.push(so_key);
+            cursor.next().unwrap();
+        }
+        so_keys
+    }
+
+    #[test]
+    fn test_map_operations() {
+        let map = ConcurrentHashMap::new();
+
+        for i in 0..100u32 {
+            assert!(map.insert(i, i * 10));
+        }
+        assert!(!map.insert(7, 0));
+        assert_eq!(map.len(), 100);
+
+        for i in 0..100u32 {
+            let entry = map.get(&i).unwrap();
+            assert_eq!((*entry.key(), *entry), (i, i * 10));
+        }
+        assert!(map.get(&100).is_none());
+
+        assert_eq!(*map.remove(&7).unwrap(), 70);
+        assert!(map.remove(&7).is_none());
+        assert!(!map.contains_key(&7));
+        assert_eq!(map.len(), 99);
+
+        let so_keys = so_keys(&map);
+        assert!(so_keys.windows(2).all(|w| w[0] < w[1]));
+        // the table has grown past its initial two buckets
+        assert!(map.size.load(Ordering::Relaxed) > 2);
+    }
+
+    #[test]
+    fn test_concurrent_hash_set() {
+        let set: Arc<ConcurrentHashSet<u32>> = Arc::new(ConcurrentHashSet::new());
+
+        const NUM_THREADS: usize = 16;
+        const KEYS: u32 = 1000;
+
+        let mut jhs = vec![];
+        for t in 0..NUM_THREADS {
+            let set_copy = Arc::clone(&set);
+            jhs.push(thread::spawn(move || {
+                let mut inserted = 0;
+                for i in 0..KEYS {
+                    if set_copy.insert((i * 7 + t as u32 * 31) % KEYS) {
+                        inserted += 1;
+                    }
+                }
+                inserted
+            }));
+        }
+        let inserted: u32 = jhs.into_iter().map(|jh| jh.join().unwrap()).sum();
+        assert_eq!(inserted, KEYS);
+        assert_eq!(set.len(), KEYS as usize);
+        assert!((0..KEYS).all(|i| set.contains(&i)));
+
+        let mut jhs = vec![];
+        for t in 0..NUM_THREADS {
+            let set_copy = Arc::clone(&set);
+            jhs.push(thread::spawn(move || {
+                let mut removed = 0;
+                for i in 0..KEYS {
+                    if set_copy.remove(&((i * 11 + t as u32 * 17) % KEYS)) {
+                        removed += 1;
+                    }
+                }
+                removed
+            }));
+        }
+        let removed: u32 = jhs.into_iter().map(|jh| jh.join().unwrap()).sum();
+        assert_eq!(removed, KEYS);
+        assert!(set.is_empty());
+        assert!(set
+            .map
+            .list
+            .first()
+            .map(|mut cursor| {
+                let mut only_sentinels = true;
+                while let Some(sentinel) =
+                    cursor.val().map(|node| matches!(*node, Node::Sentinel(_)))
+                {
+                    only_sentinels &= sentinel;
+                    cursor.next().unwrap();
+                }
+                only_sentinels
+            })
+            .unwrap());
+    }
+}
@@ -0,0 +1,231 @@
+//! Hazard pointers of the [`Hazard`](crate::reclaim::Hazard) strategy.
+//!
+//! A reader publishes the cell it is about to use in a slot before using
+//! it, and references dropped from `next` links are kept in a per-thread
+//! list of retired cells until no slot publishes them. Slots are allocated
+//! once and reused, so the garbage of a thread is bounded by the number of
+//! slots plus [`RETIRE_THRESHOLD`], whatever the other threads are doing.
+
+use std::cell::RefCell;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::cell::Cell;
+use crate::pool;
+
+/// Retired cells of a thread which trigger a scan of the slots.
+const RETIRE_THRESHOLD: usize = 64;
+
+/// Publishes the cell a reader is about to use.
+#[derive(Debug)]
+pub struct Slot {
+    ptr: AtomicPtr<()>,
+    /// Owned by a thread, either in use or cached by it.
+    active: AtomicBool,
+    next: *const Slot,
+}
+
+// SAFETY: `next` is written once before the slot is published and slots
+// are never freed
+unsafe impl Sync for Slot {}
+
+/// Every slot ever allocated; slots are leaked, and reused once released.
+static SLOTS: AtomicPtr<Slot> = AtomicPtr::new(ptr::null_mut());
+static SLOT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Retired cells left by exited threads.
+static SHARED: Mutex<Vec<Retired>> = Mutex::new(Vec::new());
+
+/// The reference a link held to a cell of a hazard list, with the type of
+/// the cell erased.
+struct Retired {
+    ptr: *const (),
+    drop: unsafe fn(*const ()),
+}
+
+// SAFETY: only cells of lists with the `Hazard` strategy are retired, and it
+// requires their values to be `Send + Sync`
+unsafe impl Send for Retired {}
+
+impl Retired {
+    fn new<T>(cell: Arc<Cell<T>>) -> Self {
+        unsafe fn drop_arc<T>(ptr: *const ()) {
+            pool::release(Arc::from_raw(ptr as *const Cell<T>));
+        }
+        Self {
+            ptr: Arc::into_raw(cell) as *const (),
+            drop: drop_arc::<T>,
+        }
+    }
+}
+
+impl Drop for Retired {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` and `drop` come from the same `Arc<Cell<T>>`
+        unsafe { (self.drop)(self.ptr) }
+    }
+}
+
+#[derive(Default)]
+struct Local {
+    free: Vec<&'static Slot>,
+    retired: Vec<Retired>,
+}
+
+impl Drop for Local {
+    fn drop(&mut self) {
+        for slot in self.free.drain(..) {
+            slot.active.store(false, Ordering::Release);
+        }
+        let kept = reclaim(std::mem::take(&mut self.retired), &protected());
+        lock_shared().extend(kept);
+    }
+}
+
+thread_local! {
+    static LOCAL: RefCell<Local> = RefCell::default();
+}
+
+fn lock_shared() -> std::sync::MutexGuard<'static, Vec<Retired>> {
+    SHARED.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// A slot owned by the calling thread, publishing nothing.
+pub(crate) fn acquire() -> &'static Slot {
+    if let Some(slot) = LOCAL.try_with(|local| local.borrow_mut().free.pop()).ok().flatten() {
+        return slot;
+    }
+    let mut head = SLOTS.load(Ordering::Acquire);
+    let mut slot = head as *const Slot;
+    // SAFETY: slots are never freed
+    while let Some(reused) = unsafe { slot.as_ref() } {
+        if !reused.active.load(Ordering::Relaxed)
+            && reused
+                .active
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        {
+            return reused;
+        }
+        slot = reused.next;
+    }
+    let new = Box::leak(Box::new(Slot {
+        ptr: AtomicPtr::default(),
+        active: AtomicBool::new(true),
+        next: head,
+    }));
+    while let Err(actual) =
+        SLOTS.compare_exchange(head, new, Ordering::AcqRel, Ordering::Acquire)
+    {
+        head = actual;
+        new.next = head;
+    }
+    SLOT_COUNT.fetch_add(1, Ordering::Relaxed);
+    new
+}
+
+impl Slot {
+    pub(crate) fn publish<T>(&self, cell: *mut Cell<T>) {
+        self.ptr.store(cell as *mut (), Ordering::SeqCst);
+    }
+
+    /// Clears the slot and hands it back to the calling thread.
+    pub(crate) fn release(&'static self) {
+        self.ptr.store(ptr::null_mut(), Ordering::Release);
+        let cached = LOCAL.try_with(|local| local.borrow_mut().free.push(self));
+        if cached.is_err() {
+            self.active.store(false, Ordering::Release);
+        }
+    }
+}
+
+/// The cells published by any slot, sorted.
+fn protected() -> Vec<*const ()> {
+    let mut protected = vec![];
+    let mut slot = SLOTS.load(Ordering::Acquire) as *const Slot;
+    // SAFETY: slots are never freed
+    while let Some(published) = unsafe { slot.as_ref() } {
+        let ptr = published.ptr.load(Ordering::SeqCst);
+        if !ptr.is_null() {
+            protected.push(ptr as *const ());
+        }
+        slot = published.next;
+    }
+    protected.sort_unstable();
+    protected
+}
+
+/// Whether a slot publishes `cell`.
+pub(crate) fn is_protected<T>(cell: *const Cell<T>) -> bool {
+    let mut slot = SLOTS.load(Ordering::Acquire) as *const Slot;
+    // SAFETY: slots are never freed
+    while let Some(published) = unsafe { slot.as_ref() } {
+        if ptr::eq(published.ptr.load(Ordering::SeqCst), cell as *const ()) {
+            return true;
+        }
+        slot = published.next;
+    }
+    false
+}
+
+/// Drops the reference `cell` once no slot publishes it.
+///
+/// Only cells of lists with the `Hazard` strategy may be retired.
+pub(crate) fn retire<T>(cell: Arc<Cell<T>>) {
+    let mut retired = Some(Retired::new(cell));
+    let scan = LOCAL.try_with(|local| {
+        let mut local = local.borrow_mut();
+        local.retired.extend(retired.take());
+        local.retired.len() >= RETIRE_THRESHOLD + SLOT_COUNT.load(Ordering::Relaxed)
+    });
+    if let Some(retired) = retired {
+        // the thread is exiting
+        lock_shared().push(retired);
+    } else if matches!(scan, Ok(true)) {
+        collect();
+    }
+}
+
+/// Drops the cells retired by the calling thread, and the shared ones,
+/// which no slot publishes.
+pub(crate) fn collect() {
+    let local = LOCAL
+        .try_with(|local| std::mem::take(&mut local.borrow_mut().retired))
+        .unwrap_or_default();
+    let shared = SHARED
+        .try_lock()
+        .map(|mut shared| std::mem::take(&mut *shared))
+        .unwrap_or_default();
+    // the slots are read after taking the cells: a reader which published
+    // one of them before it was retired by another thread is seen here
+    let protected = protected();
+
+    let mut kept = Some(reclaim(local, &protected));
+    let stored = LOCAL.try_with(|local| {
+        local.borrow_mut().retired.extend(kept.take().into_iter().flatten())
+    });
+    if stored.is_err() {
+        lock_shared().extend(kept.into_iter().flatten());
+    }
+    let kept = reclaim(shared, &protected);
+    if !kept.is_empty() {
+        lock_shared().extend(kept);
+    }
+}
+
+/// Drops the cells of `retired` which are not `protected`, and returns
+/// the others.
+fn reclaim(retired: Vec<Retired>, protected: &[*const ()]) -> Vec<Retired> {
+    let (kept, freed): (Vec<_>, Vec<_>) =
+        retired.into_iter().partition(|cell| protected.binary_search(&cell.ptr).is_ok());
+    // dropping cells may retire more of them, so no list is borrowed here
+    drop(freed);
+    kept
+}
+
+/// Number of cells retired by the calling thread and not dropped yet.
+#[cfg(test)]
+pub(crate) fn retired_count() -> usize {
+    LOCAL.with(|local| local.borrow().retired.len())
+}
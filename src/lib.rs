@@ -1,5 +1,19 @@
+pub mod bounded_stack;
 pub mod cell;
+pub mod deque;
+pub mod error;
+pub mod hash;
 pub mod list;
+pub mod list_map;
+pub mod local;
+pub mod pool;
+pub mod queue;
+pub mod reclaim;
+pub mod skiplist;
+pub mod sorted_set;
+mod hazard;
+mod node;
+mod wait;
 // use jemallocator::Jemalloc;
 //
 // #[global_allocator]
@@ -0,0 +1,86 @@
+use std::ptr;
+
+use crate::cell::Cell;
+use crate::node::Node;
+
+/// First data cell and last aux cell of a chain.
+type Ends<T, C> = (<C as Node<T>>::Strong, <C as Node<T>>::Strong);
+
+/// Detached run of data cells, each followed by its aux cell, built
+/// locally and linked into a list as a whole with a single CAS (see
+/// [`Cursor::try_insert_chain`](super::Cursor::try_insert_chain)).
+///
+/// The aux cell at the tail has a null `next` until the chain is linked.
+pub(crate) struct Chain<T, C: Node<T> = Cell<T>> {
+    ends: Option<Ends<T, C>>,
+    /// Last data cell.
+    last: Option<C::Strong>,
+}
+
+impl<T, C: Node<T>> Chain<T, C> {
+    pub(crate) fn new() -> Self {
+        Self {
+            ends: None,
+            last: None,
+        }
+    }
+
+    /// Appends `data` to the tail of the chain.
+    pub(crate) fn push(&mut self, data: T) {
+        let cell = C::new_detached(data);
+        let aux = cell
+            .next_dup()
+            .expect("detached cells are followed by an aux cell");
+        self.last = Some(cell.clone());
+        match self.ends {
+            None => self.ends = Some((cell, aux)),
+            Some((_, ref mut tail)) => {
+                tail.store_next(Some(cell));
+                *tail = aux;
+            }
+        }
+    }
+
+    /// Takes the values back out of a chain which failed to be linked.
+    pub(crate) fn into_values(self) -> Vec<T> {
+        let mut values = vec![];
+        drop(self.last);
+        let Some((head, tail)) = self.ends else {
+            return values;
+        };
+        let mut next = Some(head);
+        while let Some(cell) = next.take() {
+            if ptr::eq(&*cell, &*tail) {
+                break;
+            }
+            match C::try_unwrap(cell) {
+                Ok((data, cell_next)) => {
+                    values.extend(data);
+                    next = cell_next;
+                }
+                Err(_shared) => break,
+            }
+        }
+        values
+    }
+
+    /// First data cell and last aux cell, `None` for an empty chain.
+    pub(crate) fn ends(&self) -> Option<&Ends<T, C>> {
+        self.ends.as_ref()
+    }
+
+    /// Last data cell, `None` for an empty chain.
+    pub(crate) fn last(&self) -> Option<&C::Strong> {
+        self.last.as_ref()
+    }
+}
+
+impl<T, C: Node<T>> FromIterator<T> for Chain<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut chain = Chain::new();
+        for data in iter {
+            chain.push(data);
+        }
+        chain
+    }
+}
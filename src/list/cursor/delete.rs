@@ -1,112 +1,204 @@
-use anyhow::{Result, anyhow, Context};
+use crate::error::{ListError, Result};
+use crate::reclaim::Reclaim;
 
-use crate::cell::Cell;
+use super::{CellOf, Cursor, Ptr};
+use crate::list::Removed;
+use crate::node::Node;
 
-use super::Cursor;
-use std::{fmt::Debug, sync::Arc};
+type _2Cells<T, R> = (Ptr<T, R>, Ptr<T, R>);
+/// Outlinked run of data cells, with the aux cell following it.
+type Run<T, R> = (Vec<Ptr<T, R>>, Ptr<T, R>);
 
-type _3Cells<T> = (Arc<Cell<T>>, Arc<Cell<T>>, Arc<Cell<T>>);
-type _2Cells<T> = (Arc<Cell<T>>, Arc<Cell<T>>);
 
-
-impl<T: Debug> Cursor<T> {
-    #[allow(dead_code)]
-    fn outlink_target(&mut self) -> Result<_2Cells<T>> {
+impl<T, R: Reclaim<T>> Cursor<T, R> {
+    fn outlink_target(&mut self) -> Result<_2Cells<T, R>> {
         let target = match self.target {
-            None => {
-                return Err(anyhow!("target is none; cursor needs updating"))
-                    .context(super::NeedsUpdate)
-            }
+            None => return Err(ListError::NeedsUpdate),
             Some(ref _target) => _target,
         };
 
         if target.is_last() {
-            return Err(anyhow!("target is last; no possibility to delete"));
+            return Err(ListError::AtEnd);
         }
 
         let d = target.clone();
-        let n = target
-            .next_dup()
-            .ok_or_else(|| anyhow!("unexpected None in next"))?;
+        let n = self.next_of(target)?;
 
-        self.pre_aux
-            .swap_in_next(d.clone(), Some(n.clone()))
-            .context(super::NeedsUpdate)?;
+        self.swap_in_next(&self.pre_aux, &d, &n)
+            .map_err(|_| ListError::NeedsUpdate)?;
 
         self.target.take();
         Ok((d, n))
     }
 
-    #[allow(dead_code)]
-    fn calculate_delete_start(&self) -> Result<_2Cells<T>> {
+    fn calculate_delete_start(&self) -> Result<_2Cells<T, R>> {
         let mut p = self.pre_cell.clone();
         while let Some(q) = p.backlink_dup() {
-            p = q;
+            p = R::from_strong(q);
         }
-        let s = p
-            .next_dup()
-            .ok_or_else(|| anyhow!("unexpected None in next"))?;
+        let s = self.next_of(&p)?;
         Ok((p, s))
     }
 
-    #[allow(dead_code)]
-    fn n_is_last_aux(n: &Arc<Cell<T>>) -> Result<bool> {
-        let n_next = n
-            .next_dup()
-            .ok_or_else(|| anyhow!("unexpected None in next"))?;
+    fn n_is_last_aux(&self, n: &Ptr<T, R>) -> Result<bool> {
+        let n_next = self.next_of(n)?;
         Ok(n_next.is_normal_cell())
     }
 
-    #[allow(dead_code)]
-    fn advance_delete_end(mut n: Arc<Cell<T>>) -> Result<Arc<Cell<T>>> {
-        let mut n_next = n
-            .next_dup()
-            .ok_or_else(|| anyhow!("unexpected None in next"))?;
+    fn advance_delete_end(&self, mut n: Ptr<T, R>) -> Result<Ptr<T, R>> {
+        let mut n_next = self.next_of(&n)?;
 
         while !n_next.is_normal_cell() {
             n = n_next;
-            n_next = n
-                .next_dup()
-                .ok_or_else(|| anyhow!("unexpected None in next"))?;
+            n_next = self.next_of(&n)?;
         }
         Ok(n)
     }
 
-    #[allow(dead_code)]
-    fn try_delete(&mut self) -> Result<Arc<Cell<T>>> {
+    /// Removes the element at the cursor position.
+    ///
+    /// Fails with [`ListError::NeedsUpdate`] if the list was modified
+    /// around the cursor since its last update, and with
+    /// [`ListError::AtEnd`] if the cursor is at the end of the list.
+    pub fn try_delete(&mut self) -> Result<Removed<T, CellOf<T, R>>> {
         let (target_dropped, mut n) = self.outlink_target()?;
 
         let (p, mut s) = self.calculate_delete_start()?;
-        target_dropped.store_backlink(Some(Arc::downgrade(&p)));
+        target_dropped.set_backlink(&R::to_strong(&p));
+
+        n = self.advance_delete_end(n)?;
+        self.compact(&p, &mut s, &n)?;
 
-        n = Cursor::advance_delete_end(n)?;
+        Ok(Removed::new(R::to_strong(&target_dropped)))
+    }
+
+    /// Removes the element at the cursor position like
+    /// [`Cursor::try_delete`], provided it is the last element of the list
+    /// when it is outlinked; fails with [`ListError::NeedsUpdate`] if it is
+    /// not.
+    ///
+    /// The link of its aux cell to the last cell is frozen across the
+    /// outlink, so that no element is inserted behind it meanwhile.
+    pub(crate) fn try_delete_last(&mut self) -> Result<Removed<T, CellOf<T, R>>> {
+        let target = self.target.as_ref().ok_or(ListError::NeedsUpdate)?;
+        if target.is_last() {
+            return Err(ListError::AtEnd);
+        }
+        let a = self.advance_delete_end(self.next_of(target)?)?;
+        let last = self.next_of(&a)?;
+        if !last.is_last() || !a.freeze_next(&last) {
+            return Err(ListError::NeedsUpdate);
+        }
+        let res = self.try_delete();
+        a.thaw_next();
+        res
+    }
+
+    /// Swings the next link of `p`, the live predecessor of deleted cells,
+    /// from `s` past the auxiliary cells they left behind to `n`.
+    fn compact(&self, p: &Ptr<T, R>, s: &mut Ptr<T, R>, n: &Ptr<T, R>) -> Result<()> {
         loop {
-            let res = p.swap_in_next(s.clone(), Some(n.clone()));
+            let res = self.swap_in_next(p, s, n);
             if res.is_err() {
-                s = p
-                    .next_dup()
-                    .ok_or_else(|| anyhow!("unexpected None in next"))?;
+                *s = self.next_of(p)?;
             }
 
-            match DeleteLoopCondition::new(res.is_ok(), &p, &n)? {
+            match DeleteLoopCondition::new(res.is_ok(), self, p, n)? {
                 Failure => {}
-                Success | ConcurrentDelForward | ConcurrentDelPrev => break,
+                Success | ConcurrentDelForward | ConcurrentDelPrev => return Ok(()),
+            }
+        }
+    }
+
+    /// Outlinks the run of up to `k` elements starting at the target with
+    /// a single CAS, like [`Cursor::outlink_target`] does for one element;
+    /// returns the run and the aux cell following it.
+    ///
+    /// The link of the last aux cell before every element of the run but
+    /// the first is frozen first, so that no element is inserted into the
+    /// run or removed from it by another cursor before the CAS. The links
+    /// stay frozen once the run is detached.
+    fn outlink_range(&mut self, k: usize) -> Result<Run<T, R>> {
+        let target = match self.target {
+            None => return Err(ListError::NeedsUpdate),
+            Some(ref _target) => _target,
+        };
+        if target.is_last() {
+            return Err(ListError::AtEnd);
+        }
+
+        let mut run = vec![target.clone()];
+        let mut frozen = vec![];
+        let mut res = Ok(());
+        while run.len() < k {
+            let a = self.advance_delete_end(self.next_of(&run[run.len() - 1])?)?;
+            let d = self.next_of(&a)?;
+            if !d.is_data_cell() {
+                break;
+            }
+            if !a.freeze_next(&d) {
+                res = Err(ListError::NeedsUpdate);
+                break;
+            }
+            frozen.push(a);
+            run.push(d);
+        }
+        let n = self.next_of(&run[run.len() - 1])?;
+        let res = res.and_then(|()| {
+            self.swap_in_next(&self.pre_aux, &run[0], &n)
+                .map_err(|_| ListError::NeedsUpdate)
+        });
+        if let Err(err) = res {
+            frozen.iter().for_each(|a| a.thaw_next());
+            return Err(err);
+        }
+
+        self.target.take();
+        Ok((run, n))
+    }
+
+    /// Removes the run of up to `k` elements starting at the cursor
+    /// position, detaching it from the list with a single CAS; returns the
+    /// elements in list order, fewer if the end of the list is reached.
+    ///
+    /// Concurrent operations see the elements of the run either all in the
+    /// list or all removed. Inserts and deletes inside the run by other
+    /// cursors are retried until the run is detached, or released by a
+    /// failed attempt. Traversals are not held up.
+    pub fn delete_range(mut self, k: usize) -> Result<Vec<Removed<T, CellOf<T, R>>>> {
+        if k == 0 {
+            return Ok(vec![]);
+        }
+        let (run, mut n) = loop {
+            match self.outlink_range(k) {
+                Ok(outlinked) => break outlinked,
+                Err(ListError::NeedsUpdate) => self.update()?,
+                Err(ListError::AtEnd) => return Ok(vec![]),
+                Err(err) => return Err(err),
             }
+        };
+
+        let (p, mut s) = self.calculate_delete_start()?;
+        for d in &run {
+            d.set_backlink(&R::to_strong(&p));
         }
+        n = self.advance_delete_end(n)?;
+        self.compact(&p, &mut s, &n)?;
 
-        Ok(target_dropped)
+        let removed = run.into_iter().map(|cell| Removed::new(R::to_strong(&cell))).collect();
+        self.release();
+        Ok(removed)
     }
-    #[allow(dead_code)]
-    pub fn delete(mut self) -> Result<Arc<Cell<T>>> {
+
+    /// Removes the element at the cursor position, updating the cursor and
+    /// retrying until it succeeds.
+    pub fn delete(mut self) -> Result<Removed<T, CellOf<T, R>>> {
         loop {
             match self.try_delete() {
-                Ok(res) => return Ok(res),
-                Err(e) => {
-                    if e.downcast_ref::<super::NeedsUpdate>().is_some() {
-                        self.update()?;
-                    } else {
-                        return Err(e);
-                    }
+                Err(ListError::NeedsUpdate) => self.update()?,
+                res => {
+                    self.release();
+                    return res;
                 }
             }
         }
@@ -122,14 +214,19 @@ enum DeleteLoopCondition {
 }
 
 impl DeleteLoopCondition {
-    fn new<T:Debug>(res: bool, p: &Arc<Cell<T>>, n: &Arc<Cell<T>>) -> Result<Self> {
+    fn new<T, R: Reclaim<T>>(
+        res: bool,
+        cursor: &Cursor<T, R>,
+        p: &Ptr<T, R>,
+        n: &Ptr<T, R>,
+    ) -> Result<Self> {
         if res {
             return Ok(Self::Success);
         }
         if p.backlink_dup().is_some() {
             return Ok(Self::ConcurrentDelPrev);
         }
-        if !Cursor::n_is_last_aux(n)? {
+        if !cursor.n_is_last_aux(n)? {
             return Ok(Self::ConcurrentDelForward);
 
         }
@@ -161,10 +258,9 @@ mod tests {
 
             match cursor.try_delete() {
                 Err(e) => {
-                    assert!(e.downcast_ref::<crate::list::cursor::NeedsUpdate>()
-                        .is_some());
+                    assert_eq!(e, crate::error::ListError::NeedsUpdate);
                 },
-                Ok(_) => assert!(false),
+                Ok(_) => panic!("second delete with a stale cursor succeeded"),
              }
 
         }
@@ -172,7 +268,7 @@ mod tests {
         for i in 1..=10 {
             let mut cursor = list.first().unwrap();
             let element = cursor.try_delete().unwrap();
-            assert_eq!(element.val().unwrap(), &i);
+            assert_eq!(*element, i);
             drop(element);
             drop(cursor);
         }
@@ -180,4 +276,38 @@ mod tests {
 
     }
 
+    #[test]
+    fn test_try_delete_last() {
+        let list: crate::list::List<u32> = (0..2).collect();
+
+        let mut cursor = list.first().unwrap();
+        assert_eq!(cursor.try_delete_last().unwrap_err(), crate::error::ListError::NeedsUpdate);
+        cursor.next().unwrap();
+        assert_eq!(*cursor.try_delete_last().unwrap(), 1);
+        // the frozen link is thawed again
+        list.push_back(2);
+        assert_eq!(list.iter().map(|val| *val).collect::<Vec<_>>(), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_delete_range() {
+        let list: crate::list::List<u32> = (0..10).collect();
+
+        let mut cursor = list.first().unwrap();
+        cursor.next().unwrap();
+        // a traversal parked inside the run steps out of it
+        let mut reader = list.first().unwrap();
+        reader.advance_while(|&val| val < 3).unwrap();
+
+        let removed = cursor.delete_range(4).unwrap();
+        assert_eq!(removed.iter().map(|val| **val).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(list.iter().map(|val| *val).collect::<Vec<_>>(), vec![0, 5, 6, 7, 8, 9]);
+        reader.next().unwrap();
+        assert_eq!(reader.val().as_deref(), Some(&5));
+
+        assert_eq!(list.first().unwrap().delete_range(0).unwrap().len(), 0);
+        assert_eq!(list.first().unwrap().delete_range(10).unwrap().len(), 6);
+        assert!(list.first().unwrap().delete_range(1).unwrap().is_empty());
+    }
+
 }
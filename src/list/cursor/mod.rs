@@ -1,67 +1,211 @@
-use std::{fmt::Debug, sync::Arc};
+use std::fmt::Debug;
 
-use crate::cell::Cell;
-use anyhow::{Result, anyhow, Context};
+use crate::cell::{Borrowed, Cell};
+use crate::error::{ListError, Result};
+use crate::list::Chain;
+use crate::node::Node;
+use crate::reclaim::backend::{Backend, Strong};
+use crate::reclaim::{Counted, Reclaim};
 
 pub mod delete;
 
-pub struct Cursor<T: Debug> {
-    pub(super) target: Option<Arc<Cell<T>>>,
-    pub(super) pre_aux: Arc<Cell<T>>,
-    pub(super) pre_cell: Arc<Cell<T>>,
+/// Error of [`Cursor::try_insert`], handing back the value which was not
+/// inserted.
+pub struct InsertError<T, C: Node<T> = Cell<T>> {
+    error: ListError,
+    cell: C::Strong,
 }
 
-#[derive(Debug)]
-pub struct NeedsUpdate;
-impl std::fmt::Display for NeedsUpdate {
+impl<T, C: Node<T>> InsertError<T, C> {
+    pub fn error(&self) -> ListError {
+        self.error
+    }
+
+    /// Returns the value which was not inserted.
+    pub fn into_inner(self) -> T {
+        // the cell has never been published, so this is its only owner
+        match C::try_unwrap(self.cell) {
+            Ok((Some(data), _aux)) => data,
+            _ => unreachable!("rejected cell is an unpublished data cell"),
+        }
+    }
+
+    pub(crate) fn into_cell(self) -> C::Strong {
+        self.cell
+    }
+}
+
+impl<T: Debug, C: Node<T>> Debug for InsertError<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InsertError")
+            .field("error", &self.error)
+            .field("data", &self.cell.val())
+            .finish()
+    }
+}
+
+/// Error of [`Cursor::try_insert_many`], handing back the values which
+/// were not inserted.
+pub struct InsertManyError<T, C: Node<T> = Cell<T>> {
+    error: ListError,
+    chain: Chain<T, C>,
+}
+
+impl<T, C: Node<T>> InsertManyError<T, C> {
+    pub fn error(&self) -> ListError {
+        self.error
+    }
+
+    /// Returns the values which were not inserted, in iteration order.
+    pub fn into_inner(self) -> Vec<T> {
+        self.chain.into_values()
+    }
+}
+
+impl<T, C: Node<T>> Debug for InsertManyError<T, C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("NeedsUpdate")
+        f.debug_struct("InsertManyError")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
     }
 }
 
-impl<T: Debug> Cursor<T> {
-    pub fn new(pre_cell: Arc<Cell<T>>, pre_aux: Arc<Cell<T>>) -> Self {
-        Self {
+/// A cell referenced by a cursor of a list with reclamation strategy `R`.
+pub(crate) type Ptr<T, R> = <R as Backend<T>>::Ptr;
+
+/// Cell of a list with reclamation strategy `R`.
+pub(crate) type CellOf<T, R> = <R as Backend<T>>::Cell;
+
+/// Borrow state of the values of a list with reclamation strategy `R`.
+type StateOf<T, R> = <CellOf<T, R> as Node<T>>::State;
+
+pub struct Cursor<T, R: Reclaim<T> = Counted> {
+    pub(super) target: Option<Ptr<T, R>>,
+    pub(super) pre_aux: Ptr<T, R>,
+    pub(super) pre_cell: Ptr<T, R>,
+    guard: R::Guard,
+}
+
+impl<T, R: Reclaim<T>> Debug for Cursor<T, R>
+where
+    CellOf<T, R>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cursor")
+            .field("target", &self.target.as_deref())
+            .field("pre_aux", &*self.pre_aux)
+            .field("pre_cell", &*self.pre_cell)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, R: Reclaim<T>> Cursor<T, R> {
+    /// Cursor positioned just past `cell`, which may already have been
+    /// removed from its list.
+    pub(crate) fn after(cell: &Strong<T, R>) -> Result<Self> {
+        let (guard, pre_cell) = R::pin(cell);
+        let pre_aux = R::next(&pre_cell, &guard).ok_or(ListError::CorruptedChain)?;
+        let mut cursor = Self {
             target: None,
+            pre_aux,
             pre_cell,
+            guard,
+        };
+        cursor.update()?;
+        Ok(cursor)
+    }
+
+    /// The cell following `cell`.
+    fn next_of(&self, cell: &Ptr<T, R>) -> Result<Ptr<T, R>> {
+        R::next(cell, &self.guard).ok_or(ListError::CorruptedChain)
+    }
+
+    /// Swings the `next` link of `cell` from `expected` to `n`, retiring
+    /// the reference the link held.
+    pub(crate) fn swap_in_next(
+        &self,
+        cell: &CellOf<T, R>,
+        expected: &CellOf<T, R>,
+        n: &Ptr<T, R>,
+    ) -> Result<()> {
+        let old = cell.swap_in_next(expected, R::to_strong(n))?;
+        R::retire(old, &self.guard);
+        Ok(())
+    }
+
+    /// Drops the references of the cursor, the cells it was the last owner
+    /// of going to the cell pool (see [`pool`](crate::pool)).
+    pub(crate) fn release(self) {
+        let Cursor {
+            target,
             pre_aux,
-        }
+            pre_cell,
+            guard,
+        } = self;
+        target.into_iter().chain([pre_aux, pre_cell]).for_each(R::release);
+        drop(guard);
+    }
+
+    pub(crate) fn pre_cell(&self) -> &Ptr<T, R> {
+        &self.pre_cell
+    }
+
+    /// Data cell the cursor is positioned at.
+    pub(crate) fn target_data(&self) -> Option<&Ptr<T, R>> {
+        self.target.as_ref().filter(|target| target.is_data_cell())
+    }
+
+    /// Value of the cell the cursor is positioned at, `None` at the end
+    /// of the list.
+    pub fn val(&self) -> Option<Borrowed<'_, T, StateOf<T, R>>> {
+        self.target.as_ref().and_then(|target| target.val())
+    }
+
+    /// Returns `true` if the cursor is positioned past the last element.
+    pub fn is_at_end(&self) -> bool {
+        self.target.as_ref().is_some_and(|target| target.is_last())
     }
 
+    /// Revalidates the cursor after a concurrent modification, moving it
+    /// to the cell which now follows its previous position.
     pub fn update(&mut self) -> Result<()>{
-        match self.target {
-            None => {}
-            Some(ref target) => {
-                if self.pre_aux.next_cmp(target) {
-                    return Ok(());
-                }
+        if let Some(ref target) = self.target {
+            if self.pre_aux.next_cmp(target) && !Self::in_detached_range(&self.pre_aux, target) {
+                return Ok(());
             }
         }
 
         let mut p = self.pre_aux.clone(); // expecting aux variant
-        let mut n = p.next_dup().ok_or_else(|| anyhow!("unexpected None in next"))?;
+        let mut n = self.next_of(&p)?;
 
         drop(self.target.take());
-        while !n.is_last() && !n.is_data_cell() {
-            if let Err(err) = self.pre_cell.swap_in_next(p, Some(n.clone())) {
-                debug_assert!({
-                    println!("cursor.update {:?}", err);
-                    true
-                })
+        while !n.is_last() && (!n.is_data_cell() || Self::in_detached_range(&p, &n)) {
+            if !n.is_data_cell() {
+                // a failed swap means another cursor unlinked the aux cell
+                // first, which is fine
+                let _ = self.swap_in_next(&self.pre_cell, &p, &n);
             }
 
             p = n.clone();
-            n = n.next_dup().ok_or_else(|| anyhow!("unexpected None in next"))?;
+            n = self.next_of(&n)?;
         }
         self.pre_aux = p;
         self.target = Some(n);
         Ok(())
     }
 
-    #[allow(dead_code)]
+    /// Whether `n`, the data cell following the aux cell `p`, lies inside a
+    /// run detached by [`Cursor::delete_range`]; such cells are skipped.
+    fn in_detached_range(p: &CellOf<T, R>, n: &CellOf<T, R>) -> bool {
+        p.is_frozen() && n.is_removed()
+    }
+
+    /// Advances the cursor to the next element; returns `false` if it was
+    /// already at the end of the list.
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<bool> {
         let target = match self.target {
-            None => return Err(anyhow!("cursor in invalid state: target is None")),
+            None => return Err(ListError::InvalidCursor),
             Some(ref _target) => {
                 if _target.is_last() {
                     return Ok(false);
@@ -69,39 +213,117 @@ impl<T: Debug> Cursor<T> {
                 _target
             }
         };
+        self.pre_aux = self.next_of(target)?;
         self.pre_cell = target.clone();
-        self.pre_aux = target
-            .next_dup()
-            .ok_or_else(|| anyhow!("unexpected None in next"))?;
         self.update()?;
         Ok(true)
     }
 
-    pub fn try_insert(&self, data: T) -> Result<()> {
-        let target = match self.target {
-            None => return Err(anyhow!("target is none; cursor needs updating")).context(NeedsUpdate),
-            Some(ref _target) => _target,
+    /// Advances the cursor to the first element for which `before` returns
+    /// `false`, or to the end of the list.
+    pub fn advance_while(&mut self, mut before: impl FnMut(&T) -> bool) -> Result<()> {
+        while self.val().is_some_and(|val| before(&val)) {
+            self.next()?;
+        }
+        Ok(())
+    }
+
+    /// Inserts `data` before the cursor position with a single CAS.
+    ///
+    /// Fails with [`ListError::NeedsUpdate`] if the list was modified
+    /// around the cursor since its last update; `data` can be taken back
+    /// from the error.
+    pub fn try_insert(&self, data: T) -> std::result::Result<(), InsertError<T, CellOf<T, R>>> {
+        self.try_insert_cell(CellOf::<T, R>::new_detached(data))
+    }
+
+    /// Inserts the values of `iter` before the cursor position, in
+    /// iteration order, with a single CAS.
+    ///
+    /// The cells are linked to each other before they are published, so
+    /// concurrent traversals see either none or all of them. Fails like
+    /// [`Cursor::try_insert`]; the values can be taken back from the error.
+    pub fn try_insert_many(
+        &self,
+        iter: impl IntoIterator<Item = T>,
+    ) -> std::result::Result<(), InsertManyError<T, CellOf<T, R>>> {
+        let chain: Chain<T, CellOf<T, R>> = iter.into_iter().collect();
+        let linked = match chain.ends() {
+            Some((head, tail)) => self.try_insert_chain(head, tail),
+            None => Ok(()),
         };
-        let aux = Cell::new_aux(target.clone()); // +1 target
-        let data = Cell::new_data(data, aux);
+        linked.map_err(|error| InsertManyError { error, chain })
+    }
 
-        self
-            .pre_aux
-            .swap_in_next(target.clone(), Some(data))
-            .context(NeedsUpdate)?;
-        Ok(())
-        
+    /// Links `cell`, a data cell from [`Node::new_detached`], in before the
+    /// cursor position. The cell is handed back on failure, ready to be
+    /// retried without allocating again.
+    pub(crate) fn try_insert_cell(
+        &self,
+        cell: Strong<T, R>,
+    ) -> std::result::Result<(), InsertError<T, CellOf<T, R>>> {
+        let linked = match cell.next_dup() {
+            Some(aux) => self.try_insert_chain(&cell, &aux),
+            None => Err(ListError::CorruptedChain),
+        };
+        linked.map_err(|error| InsertError { error, cell })
     }
-}
 
-impl<T: Debug + Copy> Cursor<T> {
+    /// Links the detached chain from the data cell `head` to the aux cell
+    /// `tail` in before the cursor position with a single CAS.
+    pub(crate) fn try_insert_chain(&self, head: &Strong<T, R>, tail: &Strong<T, R>) -> Result<()> {
+        let target = self.target.as_ref().ok_or(ListError::NeedsUpdate)?;
+        R::adopt(head, tail);
+        tail.store_next(Some(R::to_strong(target))); // +1 target
+
+        let old = match self.pre_aux.swap_in_next(target, head.clone()) {
+            Ok(old) => old,
+            Err(_) => {
+                // the chain stays detached and must not keep the target,
+                // which may have been removed meanwhile
+                if let Some(target) = tail.take_next() {
+                    R::retire(target, &self.guard);
+                }
+                return Err(ListError::NeedsUpdate);
+            }
+        };
+        // the target stays referenced by `tail`, whose link is published by
+        // the same CAS, so the reference can be dropped without retiring it
+        drop(old);
+        Ok(())
+    }
 
-    #[allow(dead_code)]
+    /// Inserts `data` before the cursor position, updating the cursor and
+    /// retrying until it succeeds.
     pub fn insert(&mut self, data: T) -> Result<()> {
-        while self.try_insert(data).is_err() {
-            self.update()?;
+        let mut cell = CellOf::<T, R>::new_detached(data);
+        loop {
+            match self.try_insert_cell(cell) {
+                Ok(()) => return Ok(()),
+                Err(InsertError {
+                    error: ListError::NeedsUpdate,
+                    cell: rejected,
+                }) => {
+                    cell = rejected;
+                    self.update()?;
+                }
+                Err(err) => return Err(err.error),
+            }
         }
-        Ok(())
     }
 
+    /// Inserts the values of `iter` before the cursor position with a
+    /// single CAS, updating the cursor and retrying until it succeeds.
+    pub fn insert_many(&mut self, iter: impl IntoIterator<Item = T>) -> Result<()> {
+        let chain: Chain<T, CellOf<T, R>> = iter.into_iter().collect();
+        let Some((head, tail)) = chain.ends() else {
+            return Ok(());
+        };
+        loop {
+            match self.try_insert_chain(head, tail) {
+                Err(ListError::NeedsUpdate) => self.update()?,
+                res => return res,
+            }
+        }
+    }
 }
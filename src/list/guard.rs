@@ -0,0 +1,147 @@
+use std::fmt::Debug;
+use std::mem::ManuallyDrop;
+use std::ops::Deref;
+use std::thread;
+
+use crate::cell::{Cell, Value};
+use crate::node::Node;
+
+/// An element removed from a [`List`](super::List).
+///
+/// Derefs to the removed value, which can be moved out as soon as no
+/// [`Ref`] borrows it anymore. Cursors of concurrent traversals may still
+/// hold the underlying cell for a while; the links of the cell are released
+/// together with its last owner.
+pub struct Removed<T, C: Node<T> = Cell<T>> {
+    cell: ManuallyDrop<C::Strong>,
+}
+
+impl<T, C: Node<T>> Removed<T, C> {
+    pub(crate) fn new(cell: C::Strong) -> Self {
+        debug_assert!(cell.is_data_cell());
+        Self {
+            cell: ManuallyDrop::new(cell),
+        }
+    }
+
+    fn into_cell(self) -> C::Strong {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never dropped, so the cell is taken once
+        unsafe { ManuallyDrop::take(&mut this.cell) }
+    }
+
+    fn value(&self) -> &Value<T, C::State> {
+        self.cell.value().expect("only data cells are removed")
+    }
+
+    /// Returns the value if no [`Ref`] borrows it, otherwise gives the
+    /// guard back.
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        // SAFETY: the guard is handed out once, to the one which removed
+        // the cell
+        match unsafe { self.value().try_take() } {
+            Some(data) => Ok(data),
+            None => Err(self),
+        }
+    }
+
+    /// Returns the value, waiting for the [`Ref`]s borrowing it to be
+    /// dropped; cursors and iterators holding the cell are not waited for.
+    ///
+    /// A `Ref` held by the calling thread is never dropped meanwhile, and
+    /// neither is any `Ref` of a [`local`](crate::local) list; use
+    /// [`Removed::try_unwrap`] where one may be alive.
+    pub fn into_inner(self) -> T {
+        let mut removed = self;
+        loop {
+            match removed.try_unwrap() {
+                Ok(data) => return data,
+                Err(borrowed) => {
+                    removed = borrowed;
+                    thread::yield_now();
+                }
+            }
+        }
+    }
+}
+
+impl<T, C: Node<T>> From<Removed<T, C>> for Ref<T, C> {
+    fn from(removed: Removed<T, C>) -> Self {
+        Ref::new(removed.into_cell()).expect("the removed value was not moved out")
+    }
+}
+
+impl<T, C: Node<T>> Drop for Removed<T, C> {
+    /// Drops the value with the cell if it was its last owner, keeping the
+    /// allocation in the cell pool (see [`pool`](crate::pool)).
+    fn drop(&mut self) {
+        // SAFETY: the cell is not used after the guard is dropped
+        C::recycle(unsafe { ManuallyDrop::take(&mut self.cell) });
+    }
+}
+
+impl<T, C: Node<T>> Deref for Removed<T, C> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: only the guard may move the value out
+        unsafe { self.value().get() }
+    }
+}
+
+impl<T: Debug, C: Node<T>> Debug for Removed<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Removed").field(&**self).finish()
+    }
+}
+
+/// An element of a [`List`](super::List), kept alive while the guard is
+/// held.
+///
+/// Holding a `Ref` does not prevent the element from being removed from
+/// the list concurrently, but its value is only moved out of the removed
+/// element once no `Ref` borrows it; see [`Removed::into_inner`].
+pub struct Ref<T, C: Node<T> = Cell<T>> {
+    cell: C::Strong,
+}
+
+impl<T, C: Node<T>> Ref<T, C> {
+    /// Borrows the value of a data cell, unless it was moved out.
+    pub(crate) fn new(cell: C::Strong) -> Option<Self> {
+        cell.value()
+            .expect("refs point to data cells")
+            .acquire()
+            .then(|| Self { cell })
+    }
+
+    fn value(&self) -> &Value<T, C::State> {
+        self.cell.value().expect("refs point to data cells")
+    }
+}
+
+impl<T, C: Node<T>> Clone for Ref<T, C> {
+    fn clone(&self) -> Self {
+        Ref::new(self.cell.clone()).expect("a borrowed value is not moved out")
+    }
+}
+
+impl<T, C: Node<T>> Drop for Ref<T, C> {
+    fn drop(&mut self) {
+        self.value().release();
+    }
+}
+
+impl<T, C: Node<T>> Deref for Ref<T, C> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the value is borrowed
+        unsafe { self.value().get() }
+    }
+}
+
+impl<T: Debug, C: Node<T>> Debug for Ref<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Ref").field(&**self).finish()
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt::Debug;
+use std::iter::FusedIterator;
+
+use crate::node::Hint;
+use crate::reclaim::{Counted, Reclaim};
+
+use super::{CellOf, Chain, Cursor, List, Ref};
+
+/// Iterator over the elements of a [`List`], see [`List::iter`].
+///
+/// The iteration is weakly consistent: it tolerates concurrent inserts
+/// and deletes, never yields an element twice or out of list order, but
+/// may or may not yield elements inserted or removed after it started.
+pub struct Iter<T, R: Reclaim<T> = Counted> {
+    cursor: Cursor<T, R>,
+}
+
+impl<T, R: Reclaim<T>> Debug for Iter<T, R>
+where
+    Cursor<T, R>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Iter").field("cursor", &self.cursor).finish()
+    }
+}
+
+impl<T, R: Reclaim<T>> Iter<T, R> {
+    pub(super) fn new(cursor: Cursor<T, R>) -> Self {
+        Self { cursor }
+    }
+}
+
+impl<T, R: Reclaim<T>> Iterator for Iter<T, R> {
+    type Item = Ref<T, CellOf<T, R>>;
+
+    fn next(&mut self) -> Option<Ref<T, CellOf<T, R>>> {
+        loop {
+            let cell = R::to_strong(self.cursor.target_data()?);
+            self.cursor.next().expect("list chain is corrupted");
+            // the value of an element removed meanwhile may be moved out
+            if let Some(item) = Ref::new(cell) {
+                return Some(item);
+            }
+        }
+    }
+}
+
+impl<T, R: Reclaim<T>> FusedIterator for Iter<T, R> {}
+
+impl<T, R: Reclaim<T>> IntoIterator for &List<T, R> {
+    type Item = Ref<T, CellOf<T, R>>;
+    type IntoIter = Iter<T, R>;
+
+    fn into_iter(self) -> Iter<T, R> {
+        self.iter()
+    }
+}
+
+/// Owning iterator over the elements of a [`List`], popping them from the
+/// front; see [`List::pop_front`].
+pub struct IntoIter<T, R: Reclaim<T> = Counted> {
+    list: List<T, R>,
+}
+
+impl<T, R: Reclaim<T>> Debug for IntoIter<T, R>
+where
+    List<T, R>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IntoIter").field("list", &self.list).finish()
+    }
+}
+
+impl<T, R: Reclaim<T>> Iterator for IntoIter<T, R> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+}
+
+impl<T, R: Reclaim<T>> FusedIterator for IntoIter<T, R> {}
+
+impl<T, R: Reclaim<T>> IntoIterator for List<T, R> {
+    type Item = T;
+    type IntoIter = IntoIter<T, R>;
+
+    fn into_iter(self) -> IntoIter<T, R> {
+        IntoIter { list: self }
+    }
+}
+
+impl<T, R: Reclaim<T>> FromIterator<T> for List<T, R> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T, R: Reclaim<T>> Extend<T> for List<T, R> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        (&*self).extend(iter)
+    }
+}
+
+/// Lists are extended through shared references, like every other
+/// modification.
+impl<T, R: Reclaim<T>> Extend<T> for &List<T, R> {
+    /// Appends the elements at the back of the list, in iteration order,
+    /// like [`List::push_back`].
+    ///
+    /// The chain of cells is built up front and spliced in with a single
+    /// CAS, so concurrent traversals see either none or all of them.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let chain: Chain<T, CellOf<T, R>> = iter.into_iter().collect();
+        let (Some((head, tail)), Some(last)) = (chain.ends(), chain.last()) else {
+            return;
+        };
+        self.link_back(head, tail);
+        self.tail.store(last);
+        self.poppers.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use crate::list::List;
+
+    #[test]
+    fn test_iter() {
+        let list: List<u32> = List::new();
+        assert!(list.iter().next().is_none());
+
+        for i in (0..10).rev() {
+            list.push_front(i);
+        }
+        let values: Vec<u32> = list.iter().map(|val| *val).collect();
+        assert_eq!(values, (0..10).collect::<Vec<_>>());
+
+        let mut iter = list.iter();
+        let first = iter.next().unwrap();
+        // the element stays readable after its removal from the list
+        let removed = list.first().unwrap().delete().unwrap();
+        assert_eq!((*first, *removed), (0, 0));
+        assert_eq!(*iter.next().unwrap(), 1);
+
+        let mut sum = 0;
+        for val in &list {
+            sum += *val;
+        }
+        assert_eq!(sum, (1..10).sum());
+    }
+
+    #[test]
+    fn test_concurrent_iter() {
+        let list: Arc<List<usize>> = Arc::new(List::new());
+
+        const NUM_THREADS: usize = 4;
+        const ITER: usize = 1000;
+
+        let mut jhs = vec![];
+        for _ in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                for i in 0..ITER {
+                    list_copy.push_front(i);
+                    if i % 2 == 0 {
+                        list_copy.pop_front();
+                    }
+                }
+            }));
+        }
+        for _ in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                for _ in 0..10 {
+                    assert!(list_copy.iter().count() <= NUM_THREADS * ITER);
+                }
+            }));
+        }
+        for jh in jhs {
+            jh.join().unwrap();
+        }
+        assert_eq!(list.iter().count(), NUM_THREADS * ITER / 2);
+    }
+
+    #[test]
+    fn test_collect_extend_into_iter() {
+        let mut list: List<String> = (0..5).map(|i| i.to_string()).collect();
+        let values: Vec<String> = list.iter().map(|val| (*val).clone()).collect();
+        assert_eq!(values, ["0", "1", "2", "3", "4"]);
+
+        list.extend(["a".to_string(), "b".to_string()]);
+        list.extend(std::iter::empty());
+        let values: Vec<String> = list.into_iter().collect();
+        assert_eq!(values, ["0", "1", "2", "3", "4", "a", "b"]);
+
+        let mut iter = (0..10_000).collect::<List<u32>>().into_iter();
+        assert_eq!(iter.next(), Some(0));
+        // the remaining elements are freed with the iterator
+        drop(iter);
+    }
+
+    #[test]
+    fn test_concurrent_extend() {
+        let list: Arc<List<usize>> = Arc::new(List::new());
+
+        const NUM_THREADS: usize = 8;
+        const CHUNK: usize = 50;
+        const ITER: usize = 20;
+
+        let mut jhs = vec![];
+        for t in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                let mut list = &*list_copy;
+                for i in 0..ITER {
+                    let start = (t * ITER + i) * CHUNK;
+                    list.extend(start..start + CHUNK);
+                    list_copy.push_front(usize::MAX);
+                }
+            }));
+        }
+        for jh in jhs {
+            jh.join().unwrap();
+        }
+
+        // every chunk was linked in as a contiguous, ordered run
+        let values: Vec<usize> = list.iter().map(|val| *val).filter(|&val| val != usize::MAX).collect();
+        assert_eq!(values.len(), NUM_THREADS * ITER * CHUNK);
+        for run in values.chunks(CHUNK) {
+            assert_eq!(run[0] % CHUNK, 0);
+            assert!(run.windows(2).all(|pair| pair[1] == pair[0] + 1));
+        }
+    }
+}
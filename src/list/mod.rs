@@ -1,59 +1,334 @@
-use std::sync::Arc;
-
-use crate::cell::Cell;
-
 use std::fmt::Debug;
-use anyhow::{Result};
+use std::marker::PhantomData;
+use std::ptr;
+use std::time::{Duration, Instant};
+
+use crate::error::{ListError, Result};
+use crate::node::{Hint, Node};
+use crate::reclaim::backend::Strong;
+use crate::reclaim::{Counted, Reclaim};
+use crate::wait::Waiters;
+
+
+mod chain;
+pub mod cursor;
+mod guard;
+mod iter;
+mod stream;
+
+pub(crate) use chain::Chain;
+pub(crate) use cursor::CellOf;
+pub use cursor::{Cursor, InsertError, InsertManyError};
+pub use guard::{Ref, Removed};
+pub use iter::{IntoIter, Iter};
+pub use stream::PopStream;
+
+/// Lock-free list of Valois, with cells reclaimed following the strategy
+/// `R`; see [`reclaim`](crate::reclaim).
+pub struct List<T, R: Reclaim<T> = Counted> {
+    first: Strong<T, R>,
+    last: Strong<T, R>,
+    /// Cell most recently pushed to the back, where the next
+    /// [`List::push_back`] starts looking for `last`.
+    tail: <CellOf<T, R> as Node<T>>::Hint,
+    /// Cell preceding the element last pushed to or popped from the back,
+    /// where [`List::pop_back`] starts looking for the last element.
+    back: <CellOf<T, R> as Node<T>>::Hint,
+    /// Threads parked in [`List::pop_front_blocking`] and tasks pending
+    /// in [`List::pop_front_async`].
+    poppers: Waiters,
+    reclaim: PhantomData<R>,
+}
 
-mod cursor;
+impl<T, R: Reclaim<T>> Debug for List<T, R>
+where
+    Strong<T, R>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("List")
+            .field("first", &self.first)
+            .field("last", &self.last)
+            .finish_non_exhaustive()
+    }
+}
 
-#[allow(unused)]
-pub struct List<T: Debug> {
-    first: Arc<Cell<T>>,
-    last: Arc<Cell<T>>,
+impl<T, R: Reclaim<T>> Default for List<T, R> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-// impl<T: Debug> Drop for List<T> {
-//     fn drop(&mut self) {
-//         self.first.clone().delete_chain();
-//     }
-// }
+impl<T, R: Reclaim<T>> Drop for List<T, R> {
+    /// Cursors started on a list taken from (see [`List::take_all`]) may
+    /// still traverse the chain, so it is released like an unlinked cell.
+    fn drop(&mut self) {
+        let (guard, _) = R::pin(&self.first);
+        R::retire(self.first.clone(), &guard);
+    }
+}
 
-impl<T: Debug> List<T> {
-    #[allow(dead_code)]
-    fn new() -> Self {
-        let last = Cell::new_last();
+impl<T, R: Reclaim<T>> List<T, R> {
+    pub fn new() -> Self {
+        let last = CellOf::<T, R>::new_last();
         let last_clone = last.clone();
 
-        let aux = Cell::new_aux(last_clone);
+        let aux = CellOf::<T, R>::new_aux(last_clone);
+        let first = CellOf::<T, R>::new_first(aux.clone());
+        R::adopt(&first, &aux);
 
         List {
-            first: Cell::new_first(aux),
+            first,
             last,
+            tail: Hint::new(),
+            back: Hint::new(),
+            poppers: Waiters::new(),
+            reclaim: PhantomData,
+        }
+    }
+
+    /// Returns a cursor positioned at the first element of the list
+    /// (or at the end, if the list is empty).
+    pub fn first(&self) -> Result<Cursor<T, R>> {
+        Cursor::after(&self.first)
+    }
+
+    /// Returns `true` if the list has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.first().expect("list chain is corrupted").is_at_end()
+    }
+
+    /// Iterates over the elements from the front of the list; the
+    /// iteration is weakly consistent, see [`Iter`].
+    pub fn iter(&self) -> Iter<T, R> {
+        Iter::new(self.first().expect("list chain is corrupted"))
+    }
+
+    /// Removes the element at the front of the list and returns its value,
+    /// or `None` if the list is empty.
+    ///
+    /// Cursors of concurrent traversals may still hold the removed cell;
+    /// they are not waited for, only [`Ref`]s borrowing the value are (see
+    /// [`Removed::into_inner`]).
+    pub fn pop_front(&self) -> Option<T> {
+        match self.first().and_then(Cursor::delete) {
+            Ok(removed) => Some(removed.into_inner()),
+            Err(ListError::AtEnd) => None,
+            Err(err) => panic!("list chain is corrupted: {}", err),
+        }
+    }
+
+    /// Removes the element at the front of the list and returns it,
+    /// parking the thread while the list is empty.
+    ///
+    /// Parked threads are woken by [`List::push_front`], [`List::push_back`]
+    /// and [`Extend`], not by inserts through a [`Cursor`].
+    pub fn pop_front_blocking(&self) -> T {
+        loop {
+            if let Some(data) = self.pop_front() {
+                return data;
+            }
+            self.poppers.wait_while(|| self.is_empty());
+        }
+    }
+
+    /// Like [`List::pop_front_blocking`], but gives up and returns `None`
+    /// once `timeout` has passed.
+    pub fn pop_front_timeout(&self, timeout: Duration) -> Option<T> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(data) = self.pop_front() {
+                return Some(data);
+            }
+            if !self.poppers.wait_while_until(|| self.is_empty(), deadline) {
+                return self.pop_front();
+            }
+        }
+    }
+
+    /// Removes the element at the back of the list and returns it, or
+    /// `None` if the list is empty.
+    ///
+    /// Cells only link forward, so the last element is looked for from
+    /// the cell preceding the element last pushed to or popped from the
+    /// back. A failed delete retries from where the cursor stands, and a
+    /// deleted predecessor from the live cell its back links lead to; the
+    /// front is walked from only when the hint is stale or the last
+    /// element lies before the cursor. The link behind the element is
+    /// frozen while it is outlinked, so it is still the last one when it
+    /// is removed.
+    ///
+    /// Like [`List::pop_front`], waits for [`Ref`]s borrowing the value only.
+    pub fn pop_back(&self) -> Option<T> {
+        let mut cursor = match self.back.upgrade() {
+            Some(pred) => Cursor::<T, R>::after(&pred),
+            None => self.first(),
+        }
+        .expect("list chain is corrupted");
+        loop {
+            if !self.seek_back(&mut cursor) {
+                // the hint led into a chain detached by `List::take_all`
+                cursor = self.first().expect("list chain is corrupted");
+                continue;
+            }
+            if cursor.target_data().is_none() {
+                if ptr::eq(&**cursor.pre_cell(), &*self.first) {
+                    return None;
+                }
+                // the last element before the cursor is behind it
+                cursor = self.first().expect("list chain is corrupted");
+                continue;
+            }
+            if let Some(mut pred) = cursor.pre_cell().backlink_dup() {
+                // the predecessor was deleted, its aux cell may be unlinked
+                while let Some(back) = pred.backlink_dup() {
+                    pred = back;
+                }
+                cursor = Cursor::<T, R>::after(&pred).expect("list chain is corrupted");
+                continue;
+            }
+            self.back.store(&R::to_strong(cursor.pre_cell()));
+            match cursor.try_delete_last() {
+                Ok(removed) => return Some(removed.into_inner()),
+                Err(ListError::NeedsUpdate) => cursor.update().expect("list chain is corrupted"),
+                Err(err) => panic!("list chain is corrupted: {}", err),
+            }
+        }
+    }
+
+    /// Moves `cursor` forward to the last element of the list, or leaves it
+    /// at the end if no element follows it. Returns `false` if the cursor
+    /// ended up outside of the list.
+    fn seek_back(&self, cursor: &mut Cursor<T, R>) -> bool {
+        while let Some(target) = cursor.target_data() {
+            let next = Cursor::<T, R>::after(&R::to_strong(target)).expect("list chain is corrupted");
+            if next.is_at_end() {
+                return next.target.as_ref().is_some_and(|target| ptr::eq(&**target, &*self.last));
+            }
+            *cursor = next;
+        }
+        cursor.target.as_ref().is_some_and(|target| ptr::eq(&**target, &*self.last))
+    }
+
+    /// Removes up to `k` elements from the front of the list at once and
+    /// returns their values in list order; see [`Cursor::delete_range`].
+    ///
+    /// Like [`List::pop_front`], waits for [`Ref`]s borrowing the values only.
+    pub fn pop_front_many(&self, k: usize) -> Vec<T> {
+        self.first()
+            .and_then(|cursor| cursor.delete_range(k))
+            .expect("list chain is corrupted")
+            .into_iter()
+            .map(Removed::into_inner)
+            .collect()
+    }
+
+    /// Takes every element present in the list at one instant and returns
+    /// them, in list order, as an owning iterator.
+    ///
+    /// The elements are unlinked as a whole by swinging the aux cell after
+    /// `first` directly to `last`; the detached chain becomes the body of a
+    /// new list, and is then cut off from `last` at a last cell of its own.
+    /// Elements inserted into the chain concurrently by cursors positioned
+    /// inside it are taken too.
+    pub fn take_all(&self) -> IntoIter<T, R> {
+        let mut cursor = self.first().expect("list chain is corrupted");
+        let head = loop {
+            let Some(head) = cursor.target_data() else {
+                return List::new().into_iter();
+            };
+            // the reference the link held to `head` moves to the new list
+            match cursor.pre_aux.swap_in_next(head, self.last.clone()) {
+                Ok(head) => break head,
+                Err(_) => cursor.update().expect("list chain is corrupted"),
+            }
+        };
+        // the hint points into the detached chain
+        self.tail.clear();
+        self.back.clear();
+
+        let aux = CellOf::<T, R>::new_aux(head);
+        let first = CellOf::<T, R>::new_first(aux.clone());
+        R::adopt(&first, &aux);
+        let taken: List<T, R> = List {
+            first,
+            last: CellOf::<T, R>::new_last(),
+            tail: Hint::new(),
+            back: Hint::new(),
+            poppers: Waiters::new(),
+            reclaim: PhantomData,
+        };
+        let mut cursor = taken.first().expect("list chain is corrupted");
+        let last = R::from_strong(taken.last.clone());
+        loop {
+            cursor.advance_while(|_| true).expect("list chain is corrupted");
+            let target = cursor.target.as_ref().expect("list chain is corrupted");
+            match cursor.swap_in_next(&cursor.pre_aux, target, &last) {
+                Ok(()) => break,
+                Err(_) => cursor.update().expect("list chain is corrupted"),
+            }
         }
+        cursor.release();
+        taken.into_iter()
+    }
+
+    /// Pushes `data` onto the front of the list.
+    pub fn push_front(&self, data: T) {
+        let mut cursor = self.first().expect("list chain is corrupted");
+        cursor.insert(data).expect("list chain is corrupted");
+        self.poppers.notify_all();
     }
 
-    #[allow(dead_code)]
-    fn first(&self) -> Result<cursor::Cursor<T>>{
-        let pre_cell = self.first.clone();
-        let pre_aux = self.first.next_dup().unwrap();
-    
-        let mut c = cursor::Cursor::new(pre_cell, pre_aux);
-    
-        c.update()?;
-        Ok(c)
+    /// Appends `data` at the back of the list.
+    ///
+    /// Like the tail pointer of a Michael–Scott queue, the list remembers
+    /// the cell last pushed to the back and walks to `last` from there,
+    /// falling back to the front once that cell has been freed.
+    pub fn push_back(&self, data: T) {
+        let cell = CellOf::<T, R>::new_detached(data);
+        let aux = cell
+            .next_dup()
+            .expect("detached cells are followed by an aux cell");
+        self.link_back(&cell, &aux);
+        self.tail.store(&cell);
+        self.poppers.notify_all();
     }
 
-    
+    /// Links the detached chain from the data cell `head` to the aux cell
+    /// `tail` in before `last`, see [`List::push_back`].
+    fn link_back(&self, head: &Strong<T, R>, tail: &Strong<T, R>) {
+        let mut cursor = match self.tail.upgrade() {
+            Some(tail) => Cursor::<T, R>::after(&tail),
+            None => self.first(),
+        }
+        .expect("list chain is corrupted");
+        loop {
+            cursor
+                .advance_while(|_| true)
+                .expect("list chain is corrupted");
+            if !cursor.target.as_ref().is_some_and(|target| ptr::eq(&**target, &*self.last)) {
+                // the hint led into a chain detached by `List::take_all`
+                cursor = self.first().expect("list chain is corrupted");
+                continue;
+            }
+            match cursor.try_insert_chain(head, tail) {
+                Ok(()) => {
+                    self.back.store(&R::to_strong(cursor.pre_cell()));
+                    break;
+                }
+                Err(ListError::NeedsUpdate) => cursor.update().expect("list chain is corrupted"),
+                Err(err) => panic!("list chain is corrupted: {}", err),
+            }
+        }
+    }
 }
 #[cfg(test)]
 mod tests {
-    use std::{sync::Arc, thread};
+    use std::{sync::Arc, thread, time::Duration};
 
-    use crate::{list::cursor, cell::Cell};
+    use crate::error::{ListError, Result};
+    use crate::reclaim::{Counted, Epoch, Hazard, Reclaim};
 
-    use super:: List;
-    use anyhow::Result;
+    use super::{List, Removed};
 
 
     #[test]
@@ -80,11 +355,7 @@ mod tests {
         cursor.try_insert(42).unwrap();
 
         assert!(cursor.try_insert(42).is_err());
-        assert!(cursor
-            .try_insert(42)
-            .unwrap_err()
-            .downcast_ref::<cursor::NeedsUpdate>()
-            .is_some());
+        assert_eq!(cursor.try_insert(42).unwrap_err().error(), ListError::NeedsUpdate);
 
         cursor.update().unwrap();
 
@@ -94,13 +365,78 @@ mod tests {
         let f_aux = (*list.first).next_dup().unwrap();
         let f_val = (*f_aux).next_dup().unwrap();
 
-        assert_eq!((*f_val).val(), Some(&84));
+        assert_eq!((*f_val).val().as_deref(), Some(&84));
 
         let s_aux = (*f_val).next_dup().unwrap();
         let s_val = (*s_aux).next_dup().unwrap();
 
-        assert_eq!((*s_val).val(), Some(&42));
+        assert_eq!((*s_val).val().as_deref(), Some(&42));
     }
+    #[test]
+    fn test_insert_owned() {
+        let list: List<String> = List::new();
+
+        let mut cursor = list.first().unwrap();
+        cursor.try_insert("first".to_string()).unwrap();
+
+        let rejected = cursor.try_insert("second".to_string()).unwrap_err();
+        assert_eq!(rejected.error(), ListError::NeedsUpdate);
+        assert_eq!(rejected.into_inner(), "second");
+
+        cursor.insert("second".to_string()).unwrap();
+        drop(cursor);
+
+        assert_eq!(list.pop_front().as_deref(), Some("second"));
+        assert_eq!(list.pop_front().as_deref(), Some("first"));
+    }
+
+    #[test]
+    fn test_try_insert_many() {
+        let list: List<String> = List::new();
+
+        let mut cursor = list.first().unwrap();
+        cursor.try_insert_many(["c".to_string()]).unwrap();
+
+        let rejected = cursor
+            .try_insert_many(["a".to_string(), "b".to_string()])
+            .unwrap_err();
+        assert_eq!(rejected.error(), ListError::NeedsUpdate);
+        assert_eq!(rejected.into_inner(), ["a", "b"]);
+
+        cursor.update().unwrap();
+        cursor.try_insert_many(Vec::new()).unwrap();
+        cursor.try_insert_many(["a".to_string(), "b".to_string()]).unwrap();
+
+        let mut stale = list.first().unwrap();
+        cursor.update().unwrap();
+        cursor.insert_many(["x".to_string()]).unwrap();
+        stale.insert_many(["0".to_string(), "1".to_string()]).unwrap();
+        drop((cursor, stale));
+
+        let values: Vec<String> = list.into_iter().collect();
+        assert_eq!(values, ["0", "1", "x", "a", "b", "c"]);
+
+        // a rejected chain does not take the cell it was pointed at along
+        let list: List<String> = List::new();
+        list.push_front("x".to_string());
+        let stale = list.first().unwrap();
+        drop(list.first().unwrap().delete().unwrap());
+        let rejected = stale
+            .try_insert_many(["a".to_string(), "b".to_string()])
+            .unwrap_err();
+        drop(stale);
+        assert_eq!(rejected.into_inner(), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_non_debug_values() {
+        let list: List<Box<dyn Fn() -> u32>> = List::new();
+        list.push_front(Box::new(|| 42));
+
+        let f = list.pop_front().unwrap();
+        assert_eq!(f(), 42);
+    }
+
     const ITER: usize = 1000;
 
     #[test]
@@ -121,6 +457,23 @@ mod tests {
 
     }
 
+    #[test]
+    fn test_cursor_val() {
+        let list: List<u32> = List::new();
+        for i in (0..10).rev() {
+            list.push_front(i);
+        }
+
+        let mut cursor = list.first().unwrap();
+        let mut values = vec![];
+        while let Some(val) = cursor.val().map(|val| *val) {
+            values.push(val);
+            cursor.next().unwrap();
+        }
+        assert!(cursor.is_at_end());
+        assert_eq!(values, (0..10).collect::<Vec<_>>());
+    }
+
     #[test]
     fn test_concurrent_treiber_stacking() {
         let list: Arc<List<u32>> = Arc::new(List::new());
@@ -128,13 +481,11 @@ mod tests {
         const NUM_THREADS: usize = 1000;
         const ITER: usize = 10000;
 
-        // helping in manual drop; as too many elements may 
-        // stack overflow in recursive drop
         let mut vec_del_jh = vec![];
         for _ in 0..NUM_THREADS {
             let list_copy = Arc::clone(&list);
-            let jh = thread::Builder::new().stack_size(262*1024*1024).spawn
-                (move || -> Result<Vec<Arc<Cell<u32>>>> {
+            let jh = thread::Builder::new().spawn
+                (move || -> Result<Vec<Removed<u32>>> {
                 let mut vec = vec![];
 
                 for _ in 0..ITER {
@@ -164,19 +515,372 @@ mod tests {
         assert_eq!(count, 0);
         drop(cursor);
 
+        drop(collector);
+    }
 
-        for subvec in collector {
-            for elem in subvec {
-                elem.drop_links();
-                drop(elem);
+    #[test]
+    fn test_drop_long_list() {
+        const LEN: usize = 10_000_000;
+
+        let jh = thread::Builder::new()
+            .stack_size(2 * 1024 * 1024)
+            .spawn(|| {
+                let list: List<u32> = List::new();
+                let mut cursor = list.first().unwrap();
+                for _ in 0..LEN {
+                    cursor.try_insert(42).unwrap();
+                    cursor.update().unwrap();
+                }
+                drop(cursor);
+                drop(list);
+            })
+            .unwrap();
+        jh.join().unwrap();
+    }
 
-            }
+
+
+
+    #[test]
+    fn test_push_pop_front() {
+        let list: List<u32> = List::new();
+        assert_eq!(list.pop_front(), None);
+
+        for i in 0..ITER as u32 {
+            list.push_front(i);
+        }
+        for i in (0..ITER as u32).rev() {
+            assert_eq!(list.pop_front(), Some(i));
         }
+        assert_eq!(list.pop_front(), None);
+    }
+
+    #[test]
+    fn test_concurrent_pop_front_many() {
+        let list: Arc<List<usize>> = Arc::new(List::new());
+
+        const NUM_THREADS: usize = 8;
+        const ITER: usize = 200;
+        const BATCH: usize = 5;
 
+        let mut jhs = vec![];
+        for t in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                let mut popped = vec![];
+                for i in 0..ITER {
+                    let start = (t * ITER + i) * BATCH;
+                    (&*list_copy).extend(start..start + BATCH);
+                    popped.extend(list_copy.pop_front_many(BATCH));
+                }
+                popped
+            }));
+        }
+        let mut all: Vec<usize> = jhs
+            .into_iter()
+            .flat_map(|jh| jh.join().unwrap())
+            .collect();
+        all.extend(list.pop_front_many(usize::MAX));
+        all.sort_unstable();
+        assert_eq!(all, (0..NUM_THREADS * ITER * BATCH).collect::<Vec<_>>());
+        assert!(list.pop_front_many(1).is_empty());
     }
 
+    #[test]
+    fn test_concurrent_pop_front_many_inside() {
+        const INITIAL: usize = 20_000;
+        const NUM_THREADS: usize = 6;
+        const ITER: usize = 3000;
+        let list: Arc<List<usize>> = Arc::new((0..INITIAL).collect());
+
+        let mut jhs = vec![];
+        for t in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                let mut removed = vec![];
+                for i in 0..ITER {
+                    match t % 3 {
+                        0 => removed.extend(list_copy.pop_front_many(4)),
+                        // inserts and deletes land inside the popped runs
+                        1 => {
+                            let mut cursor = list_copy.first().unwrap();
+                            cursor.next().unwrap();
+                            cursor.insert(INITIAL + t * ITER + i).unwrap();
+                        }
+                        _ => {
+                            let mut cursor = list_copy.first().unwrap();
+                            cursor.next().unwrap();
+                            if let Ok(val) = cursor.delete() {
+                                removed.push(val.into_inner());
+                            }
+                        }
+                    }
+                }
+                removed
+            }));
+        }
+        let mut all: Vec<usize> = jhs.into_iter().flat_map(|jh| jh.join().unwrap()).collect();
+        all.extend(list.take_all());
+        all.sort_unstable();
+        let mut expected: Vec<usize> = (0..INITIAL).collect();
+        for t in (0..NUM_THREADS).filter(|t| t % 3 == 1) {
+            expected.extend(INITIAL + t * ITER..INITIAL + (t + 1) * ITER);
+        }
+        assert_eq!(all, expected);
+    }
 
+    #[test]
+    fn test_take_all() {
+        let list: List<u32> = List::new();
+        assert!(list.take_all().next().is_none());
+
+        (&list).extend(0..5);
+        let mut reader = list.first().unwrap();
+        reader.advance_while(|&val| val < 3).unwrap();
+
+        let mut taken = list.take_all();
+        assert_eq!(list.iter().count(), 0);
+        list.push_front(42);
+        // a cursor parked in the taken chain now works on the taken elements
+        reader.delete().unwrap();
+        assert_eq!(taken.next(), Some(0));
+        assert_eq!(taken.collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert_eq!(list.pop_front(), Some(42));
+
+        // elements pushed to the back after a take stay in the list
+        list.push_back(1);
+        let taken = list.take_all();
+        list.push_back(2);
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(taken.collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn test_concurrent_take_all() {
+        let list: Arc<List<usize>> = Arc::new(List::new());
+
+        const NUM_THREADS: usize = 4;
+        const ITER: usize = 2000;
+
+        let mut jhs = vec![];
+        for t in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                for i in 0..ITER {
+                    list_copy.push_front(t * ITER + i);
+                }
+                vec![]
+            }));
+        }
+        for _ in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                let mut taken = vec![];
+                for _ in 0..100 {
+                    taken.extend(list_copy.take_all());
+                }
+                taken
+            }));
+        }
+        let mut all: Vec<usize> = jhs
+            .into_iter()
+            .flat_map(|jh| jh.join().unwrap())
+            .collect();
+        all.extend(list.take_all());
+        all.sort_unstable();
+        assert_eq!(all, (0..NUM_THREADS * ITER).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_push_back() {
+        let list: List<u32> = List::new();
+        list.push_back(1);
+        list.push_front(0);
+        list.push_back(2);
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.pop_front(), Some(1));
+        // the tail hint points at a popped cell
+        assert_eq!(list.pop_front(), Some(2));
+        list.push_back(3);
+        list.push_back(4);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![3, 4]);
+    }
 
+    #[test]
+    fn test_pop_back() {
+        let list: List<u32> = (0..5).collect();
+        assert_eq!(list.pop_back(), Some(4));
+        assert_eq!(list.pop_back(), Some(3));
+        list.push_back(5);
+        assert_eq!(list.pop_back(), Some(5));
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_back(), Some(1));
+        assert_eq!(list.pop_back(), None);
+        list.push_back(6);
+        assert_eq!(list.pop_front(), Some(6));
+    }
+
+    #[test]
+    fn test_pop_front_blocking() {
+        let list: Arc<List<u32>> = Arc::new(List::new());
+        assert!(list.is_empty());
+        assert!(list.pop_front_timeout(Duration::from_millis(10)).is_none());
+
+        let list_copy = Arc::clone(&list);
+        let consumer = thread::spawn(move || {
+            let first = list_copy.pop_front_blocking();
+            let second = list_copy.pop_front_timeout(Duration::from_secs(60));
+            (first, second)
+        });
+        thread::sleep(Duration::from_millis(10));
+        list.push_front(1);
+        thread::sleep(Duration::from_millis(10));
+        (&*list).extend([2]);
+        assert_eq!(consumer.join().unwrap(), (1, Some(2)));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_pop_front_blocking() {
+        let list: Arc<List<usize>> = Arc::new(List::new());
+
+        const NUM_THREADS: usize = 4;
+        const ITER: usize = 500;
+
+        let mut consumers = vec![];
+        for _ in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            consumers.push(thread::spawn(move || {
+                (0..ITER).map(|_| list_copy.pop_front_blocking()).collect::<Vec<_>>()
+            }));
+        }
+        let mut producers = vec![];
+        for t in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            producers.push(thread::spawn(move || {
+                for i in 0..ITER {
+                    if i % 2 == 0 {
+                        list_copy.push_front(t * ITER + i);
+                    } else {
+                        list_copy.push_back(t * ITER + i);
+                    }
+                }
+            }));
+        }
+        for jh in producers {
+            jh.join().unwrap();
+        }
+        let mut all: Vec<usize> = consumers
+            .into_iter()
+            .flat_map(|jh| jh.join().unwrap())
+            .collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..NUM_THREADS * ITER).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_removed_try_unwrap() {
+        let list: List<u32> = List::new();
+        list.push_front(7);
+
+        let reader = list.iter().next().unwrap();
+        let removed = list.first().unwrap().delete().unwrap();
+        assert_eq!(*removed, 7);
+
+        let removed = removed.try_unwrap().unwrap_err();
+        drop(reader);
+        assert_eq!(removed.try_unwrap().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_pop_front_referenced() {
+        let list: List<u32> = (0..3).collect();
+        let mut iter = list.iter();
+        let cursor = list.first().unwrap();
+
+        // the pop does not wait for cursors and iterators at the cell
+        assert_eq!(list.pop_front(), Some(0));
+        assert!(cursor.val().is_none());
+        assert_eq!(iter.next().as_deref(), Some(&1));
+    }
+
+    #[test]
+    fn test_concurrent_push_pop_front() {
+        concurrent_push_pop_front::<Counted>();
+        concurrent_push_pop_front::<Epoch>();
+        concurrent_push_pop_front::<Hazard>();
+    }
+
+    fn concurrent_push_pop_front<R: Reclaim<usize> + 'static>()
+    where
+        List<usize, R>: Send + Sync,
+    {
+        let list: Arc<List<usize, R>> = Arc::new(List::new());
+
+        const NUM_THREADS: usize = 8;
+        const ITER: usize = 1000;
+
+        let mut jhs = vec![];
+        for t in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                let mut popped = vec![];
+                for i in 0..ITER {
+                    list_copy.push_front(t * ITER + i);
+                    popped.push(list_copy.pop_front().unwrap());
+                }
+                popped
+            }));
+        }
+        let mut all: Vec<usize> = jhs
+            .into_iter()
+            .flat_map(|jh| jh.join().unwrap())
+            .collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..NUM_THREADS * ITER).collect::<Vec<_>>());
+        assert_eq!(list.pop_front(), None);
+    }
+
+    #[test]
+    fn test_concurrent_push_pop_back() {
+        concurrent_push_pop_back::<Counted>();
+        concurrent_push_pop_back::<Epoch>();
+        concurrent_push_pop_back::<Hazard>();
+    }
+
+    fn concurrent_push_pop_back<R: Reclaim<usize> + 'static>()
+    where
+        List<usize, R>: Send + Sync,
+    {
+        let list: Arc<List<usize, R>> = Arc::new(List::new());
+
+        const NUM_THREADS: usize = 8;
+        const ITER: usize = 1000;
+
+        let mut jhs = vec![];
+        for t in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                let mut popped = vec![];
+                for i in 0..ITER {
+                    list_copy.push_back(t * ITER + i);
+                    if i % 2 == 0 {
+                        popped.extend(list_copy.pop_front());
+                    }
+                    popped.extend(list_copy.pop_back());
+                }
+                popped
+            }));
+        }
+        let mut all: Vec<usize> = jhs
+            .into_iter()
+            .flat_map(|jh| jh.join().unwrap())
+            .collect();
+        all.extend(list.take_all());
+        all.sort_unstable();
+        assert_eq!(all, (0..NUM_THREADS * ITER).collect::<Vec<_>>());
+    }
 
     #[test]
     fn test_set_backlink() {
@@ -0,0 +1,158 @@
+use std::fmt::Debug;
+use std::future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::{FusedStream, Stream};
+
+use crate::reclaim::{Counted, Reclaim};
+
+use super::List;
+
+impl<T, R: Reclaim<T>> List<T, R> {
+    /// Removes the element at the front of the list, registering the
+    /// task to be woken by the next push if the list is empty.
+    pub fn poll_pop_front(&self, cx: &mut Context<'_>) -> Poll<T> {
+        loop {
+            if let Some(data) = self.pop_front() {
+                return Poll::Ready(data);
+            }
+            // the list is checked again after registering, so a push
+            // racing with the registration is not missed
+            if self.poppers.register(cx.waker(), || self.is_empty()) {
+                return Poll::Pending;
+            }
+        }
+    }
+
+    /// Removes the element at the front of the list, waiting for a push
+    /// while it is empty.
+    ///
+    /// Cancelling the future leaves the list unchanged.
+    pub async fn pop_front_async(&self) -> T {
+        future::poll_fn(|cx| self.poll_pop_front(cx)).await
+    }
+
+    /// Stream of the elements popped from the front of the list, as they
+    /// are pushed; see [`PopStream`].
+    pub fn pop_stream(&self) -> PopStream<'_, T, R> {
+        PopStream { list: self }
+    }
+}
+
+/// Never ending stream of the elements popped from the front of a
+/// [`List`], see [`List::pop_stream`].
+pub struct PopStream<'a, T, R: Reclaim<T> = Counted> {
+    list: &'a List<T, R>,
+}
+
+impl<T, R: Reclaim<T>> Debug for PopStream<'_, T, R>
+where
+    List<T, R>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PopStream").field("list", self.list).finish()
+    }
+}
+
+impl<T, R: Reclaim<T>> Stream for PopStream<'_, T, R> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.list.poll_pop_front(cx).map(Some)
+    }
+}
+
+impl<T, R: Reclaim<T>> FusedStream for PopStream<'_, T, R> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::{self, Thread};
+    use std::time::Duration;
+
+    use futures_core::Stream;
+
+    use crate::list::List;
+
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut fut = pin!(fut);
+        loop {
+            match fut.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_pop_front_async() {
+        let list: Arc<List<u32>> = Arc::new(List::new());
+        list.push_front(1);
+        assert_eq!(block_on(list.pop_front_async()), 1);
+
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        assert!(list.poll_pop_front(&mut cx).is_pending());
+        assert!(list.poll_pop_front(&mut cx).is_pending());
+        list.push_front(2);
+        assert_eq!(list.poll_pop_front(&mut cx), Poll::Ready(2));
+
+        let list_copy = Arc::clone(&list);
+        let jh = thread::spawn(move || block_on(list_copy.pop_front_async()));
+        thread::sleep(Duration::from_millis(50));
+        list.push_front(3);
+        assert_eq!(jh.join().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_concurrent_pop_stream() {
+        let list: Arc<List<usize>> = Arc::new(List::new());
+
+        const NUM_THREADS: usize = 4;
+        const ITER: usize = 1000;
+
+        let mut jhs = vec![];
+        for _ in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                let mut stream = list_copy.pop_stream();
+                let mut values = vec![];
+                while values.len() < ITER {
+                    let next = std::future::poll_fn(|cx| pin!(&mut stream).poll_next(cx));
+                    values.push(block_on(next).unwrap());
+                }
+                values
+            }));
+        }
+        for i in 0..NUM_THREADS * ITER {
+            if i % 2 == 0 {
+                list.push_front(i);
+            } else {
+                list.push_back(i);
+            }
+        }
+
+        let mut values: Vec<usize> = jhs.into_iter().flat_map(|jh| jh.join().unwrap()).collect();
+        values.sort_unstable();
+        assert_eq!(values, (0..NUM_THREADS * ITER).collect::<Vec<_>>());
+        assert!(list.is_empty());
+    }
+}
@@ -0,0 +1,359 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::cell::{Borrowed, Cell};
+use crate::error::ListError;
+use crate::list::{self, Cursor, List, Ref, Removed};
+
+/// Lock-free map keeping `(K, V)` entries in key order in a [`List`].
+///
+/// A value is never modified in place: [`ListMap::insert`] and
+/// [`ListMap::compute_if_present`] link a new entry in front of the old one
+/// and then remove the old one, so that readers holding a [`ValueRef`] are
+/// never blocked by writers.
+#[derive(Debug)]
+pub struct ListMap<K, V> {
+    list: List<(K, V)>,
+}
+
+/// Value of a [`ListMap`] entry, kept alive while the guard is held.
+#[derive(Debug, Clone)]
+pub struct ValueRef<K, V> {
+    entry: Ref<(K, V)>,
+}
+
+impl<K, V> ValueRef<K, V> {
+    pub fn key(&self) -> &K {
+        &self.entry.0
+    }
+}
+
+impl<K, V> Deref for ValueRef<K, V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        &self.entry.1
+    }
+}
+
+/// Iterator over the entries of a [`ListMap`], see [`ListMap::iter`].
+#[derive(Debug)]
+pub struct Iter<K, V> {
+    entries: list::Iter<(K, V)>,
+    last: Option<Ref<(K, V)>>,
+}
+
+impl<K: Ord, V> Iterator for Iter<K, V> {
+    type Item = ValueRef<K, V>;
+
+    fn next(&mut self) -> Option<ValueRef<K, V>> {
+        loop {
+            let entry = self.entries.next()?;
+            // an entry shadowed by an insert in progress follows the new one
+            if self.last.as_ref().is_some_and(|last| last.0 == entry.0) {
+                continue;
+            }
+            self.last = Some(entry.clone());
+            return Some(ValueRef { entry });
+        }
+    }
+}
+
+impl<K, V> Default for ListMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> ListMap<K, V> {
+    pub fn new() -> Self {
+        Self { list: List::new() }
+    }
+}
+
+type Entry<K, V> = Arc<Cell<(K, V)>>;
+/// Entry shadowed by a linked cell, or the one which stopped the insertion.
+type Linked<K, V> = Result<Option<Entry<K, V>>, Option<Entry<K, V>>>;
+
+/// Key of `cell`, unless the entry has been removed and moved out.
+fn key_of<K, V>(cell: &Entry<K, V>) -> Option<Borrowed<'_, K>> {
+    Some(Borrowed::map(cell.val()?, |(key, _)| key))
+}
+
+impl<K: Ord, V> ListMap<K, V> {
+    /// Cursor at the first entry with a key not less than `key`.
+    fn seek(&self, key: &K) -> Cursor<(K, V)> {
+        let mut cursor = self.list.first().expect("list chain is corrupted");
+        Self::advance(&mut cursor, key);
+        cursor
+    }
+
+    fn advance(cursor: &mut Cursor<(K, V)>, key: &K) {
+        cursor
+            .advance_while(|(k, _)| k < key)
+            .expect("list chain is corrupted");
+    }
+
+    /// Entry the cursor is positioned at, if its key is `key`.
+    fn found(cursor: &Cursor<(K, V)>, key: &K) -> Option<Entry<K, V>> {
+        cursor
+            .target_data()
+            .filter(|target| key_of(target).is_some_and(|k| *k == *key))
+            .cloned()
+    }
+
+    /// Links `cell` in front of the first entry with a key not less than
+    /// its own, retrying from the current position under contention.
+    ///
+    /// `stop` is consulted before every attempt with the entry of the same
+    /// key currently in the map, if any; the insertion is abandoned if it
+    /// returns `true`. On success returns the entry which `cell` shadows.
+    fn link(
+        &self,
+        cell: &Entry<K, V>,
+        mut stop: impl FnMut(Option<&Entry<K, V>>) -> bool,
+    ) -> Linked<K, V> {
+        let key = key_of(cell).expect("detached cell holds a value");
+        let key = &*key;
+        let mut cursor = self.seek(key);
+        loop {
+            let old = Self::found(&cursor, key);
+            if stop(old.as_ref()) {
+                return Err(old);
+            }
+            match cursor.try_insert_cell(cell.clone()) {
+                Ok(()) => return Ok(old),
+                Err(err) if err.error() == ListError::NeedsUpdate => {
+                    cursor.update().expect("list chain is corrupted");
+                    Self::advance(&mut cursor, key);
+                }
+                Err(err) => panic!("list chain is corrupted: {}", err.error()),
+            }
+        }
+    }
+
+    /// Entries of the same key following `cell`, i.e. shadowed by it.
+    fn shadowed_by(cell: &Entry<K, V>) -> Vec<Entry<K, V>> {
+        let Some(key) = key_of(cell) else {
+            return vec![];
+        };
+        let key = &*key;
+        let mut cursor = Cursor::after(cell).expect("list chain is corrupted");
+        let mut shadowed = vec![];
+        while let Some(entry) = Self::found(&cursor, key) {
+            shadowed.push(entry);
+            cursor.next().expect("list chain is corrupted");
+        }
+        shadowed
+    }
+
+    /// Removes `old`, an entry shadowed by `new`, unless it has been removed
+    /// concurrently.
+    fn unlink_shadowed(
+        new: &Entry<K, V>,
+        old: &Entry<K, V>,
+    ) -> Option<Removed<(K, V)>> {
+        let key = key_of(old)?;
+        let key = &*key;
+        let mut cursor = Cursor::after(new).expect("list chain is corrupted");
+        // entries of the same key linked in the meantime are skipped
+        while let Some(target) = Self::found(&cursor, key) {
+            if !Arc::ptr_eq(&target, old) {
+                cursor.next().expect("list chain is corrupted");
+                continue;
+            }
+            match cursor.try_delete() {
+                Ok(removed) => return Some(removed),
+                Err(ListError::NeedsUpdate) => cursor.update().expect("list chain is corrupted"),
+                Err(err) => panic!("list chain is corrupted: {}", err),
+            }
+        }
+        None
+    }
+
+    /// Iterates over the entries in key order, each key once; the
+    /// iteration is weakly consistent, like [`List::iter`].
+    pub fn iter(&self) -> Iter<K, V> {
+        Iter {
+            entries: self.list.iter(),
+            last: None,
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<ValueRef<K, V>> {
+        Self::found(&self.seek(key), key)
+            .and_then(Ref::new)
+            .map(|entry| ValueRef { entry })
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        Self::found(&self.seek(key), key).is_some()
+    }
+
+    /// Inserts `value` under `key` and returns the entry it replaced.
+    pub fn insert(&self, key: K, value: V) -> Option<Removed<(K, V)>> {
+        let cell = Cell::new_detached((key, value));
+        match self.link(&cell, |_| false) {
+            Ok(Some(old)) => Self::unlink_shadowed(&cell, &old),
+            Ok(None) => None,
+            Err(_) => unreachable!("insertion is never abandoned"),
+        }
+    }
+
+    /// Returns the value under `key`, inserting the one produced by `f` if
+    /// there is none. `f` is called at most once.
+    pub fn get_or_insert_with(&self, key: K, f: impl FnOnce() -> V) -> ValueRef<K, V> {
+        if let Some(entry) = Self::found(&self.seek(&key), &key).and_then(Ref::new) {
+            return ValueRef { entry };
+        }
+        let cell = Cell::new_detached((key, f()));
+        let entry = Ref::new(cell.clone()).expect("detached cell holds a value");
+        loop {
+            match self.link(&cell, |old| old.is_some()) {
+                Ok(_) => return ValueRef { entry },
+                Err(old) => {
+                    let old = old.expect("insertion is abandoned for a present key only");
+                    // retried if the present entry is moved out meanwhile
+                    if let Some(entry) = Ref::new(old) {
+                        return ValueRef { entry };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Replaces the value under `key` with the one computed by `f` from the
+    /// current value, if there is one, and returns the new value.
+    ///
+    /// `f` may be called again if the value is replaced concurrently; no
+    /// concurrent update is lost.
+    pub fn compute_if_present(
+        &self,
+        key: &K,
+        mut f: impl FnMut(&V) -> V,
+    ) -> Option<ValueRef<K, V>>
+    where
+        K: Clone,
+    {
+        let mut cursor = self.seek(key);
+        loop {
+            let old = Self::found(&cursor, key)?;
+            let Some(value) = old.val().map(|old| f(&old.1)) else {
+                // removed and moved out meanwhile
+                cursor.update().expect("list chain is corrupted");
+                Self::advance(&mut cursor, key);
+                continue;
+            };
+            let cell = Cell::new_detached((key.clone(), value));
+            let entry = Ref::new(cell.clone()).expect("detached cell holds a value");
+            match cursor.try_insert_cell(cell.clone()) {
+                Ok(()) => {
+                    drop(cursor);
+                    Self::unlink_shadowed(&cell, &old);
+                    return Some(ValueRef { entry });
+                }
+                Err(err) if err.error() == ListError::NeedsUpdate => {
+                    cursor.update().expect("list chain is corrupted");
+                    Self::advance(&mut cursor, key);
+                }
+                Err(err) => panic!("list chain is corrupted: {}", err.error()),
+            }
+        }
+    }
+
+    /// Removes the entry under `key` and returns it.
+    ///
+    /// Entries it shadows, left by an insert which is still in progress,
+    /// are removed as well.
+    pub fn remove(&self, key: &K) -> Option<Removed<(K, V)>> {
+        let mut cursor = self.seek(key);
+        loop {
+            let entry = Self::found(&cursor, key)?;
+            let shadowed = Self::shadowed_by(&entry);
+            match cursor.try_delete() {
+                Ok(removed) => {
+                    for old in shadowed {
+                        Self::unlink_shadowed(&entry, &old);
+                    }
+                    return Some(removed);
+                }
+                Err(ListError::NeedsUpdate) => {
+                    cursor.update().expect("list chain is corrupted");
+                    Self::advance(&mut cursor, key);
+                }
+                Err(err) => panic!("list chain is corrupted: {}", err),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use super::ListMap;
+
+    fn entries(map: &ListMap<u32, u32>) -> Vec<(u32, u32)> {
+        map.iter().map(|entry| (*entry.key(), *entry)).collect()
+    }
+
+    #[test]
+    fn test_map_operations() {
+        let map = ListMap::new();
+
+        assert!(map.insert(2, 20).is_none());
+        assert!(map.insert(1, 10).is_none());
+        let old = map.insert(2, 21).unwrap();
+        assert_eq!(*old, (2, 20));
+        assert_eq!(entries(&map), vec![(1, 10), (2, 21)]);
+
+        let value = map.get(&2).unwrap();
+        assert_eq!((*value.key(), *value), (2, 21));
+        assert!(map.get(&3).is_none());
+
+        assert_eq!(*map.get_or_insert_with(1, || unreachable!()), 10);
+        assert_eq!(*map.get_or_insert_with(3, || 30), 30);
+
+        assert_eq!(*map.compute_if_present(&3, |v| v + 1).unwrap(), 31);
+        assert!(map.compute_if_present(&4, |v| v + 1).is_none());
+        // the guard still sees the value it was taken for
+        assert_eq!(*value, 21);
+
+        assert_eq!(*map.remove(&2).unwrap(), (2, 21));
+        drop(value);
+        assert_eq!(map.remove(&1).unwrap().into_inner(), (1, 10));
+        assert!(map.remove(&2).is_none());
+        assert!(!map.contains_key(&2));
+        assert_eq!(entries(&map), vec![(3, 31)]);
+    }
+
+    #[test]
+    fn test_concurrent_compute_if_present() {
+        let map: Arc<ListMap<u32, u32>> = Arc::new(ListMap::new());
+
+        const NUM_THREADS: usize = 16;
+        const KEYS: u32 = 10;
+        const ITER: u32 = 200;
+
+        let mut jhs = vec![];
+        for _ in 0..NUM_THREADS {
+            let map_copy = Arc::clone(&map);
+            jhs.push(thread::spawn(move || {
+                for i in 0..ITER {
+                    let key = i % KEYS;
+                    map_copy.get_or_insert_with(key, || 0);
+                    map_copy.compute_if_present(&key, |v| v + 1).unwrap();
+                }
+            }));
+        }
+        for jh in jhs {
+            jh.join().unwrap();
+        }
+
+        let expected = NUM_THREADS as u32 * ITER / KEYS;
+        assert_eq!(
+            entries(&map),
+            (0..KEYS).map(|key| (key, expected)).collect::<Vec<_>>()
+        );
+    }
+}
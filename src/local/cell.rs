@@ -0,0 +1,281 @@
+use std::cell::Cell;
+use std::ptr;
+use std::rc::{Rc, Weak};
+
+use crate::cell::Value;
+use crate::error::{ListError, Result};
+use crate::node::{self, State, Unwrapped};
+
+/// Links of a [`Node`]; the single-threaded counterpart of
+/// [`Links`](crate::cell::Links), with `Rc` references in `Cell`s.
+pub struct Links<T> {
+    next: Cell<Option<Rc<Node<T>>>>,
+    /// Makes every swing of `next` fail, see [`node::Node::freeze_next`].
+    frozen: Cell<bool>,
+    back_link: Cell<Option<Weak<Node<T>>>>,
+}
+
+/// Cell of a [`List`](super::List), with the same variants as
+/// [`Cell`](crate::cell::Cell).
+#[derive(Debug)]
+pub enum Node<T> {
+    Data { links: Links<T>, data: Value<T, Cell<usize>> },
+    Aux { links: Links<T> },
+    First { links: Links<T> },
+    Last,
+}
+
+impl State for Cell<usize> {
+    fn add(&self, count: usize) -> usize {
+        let prev = self.get();
+        self.set(prev + count);
+        prev
+    }
+
+    fn sub(&self, count: usize) {
+        self.set(self.get() - count);
+    }
+
+    fn replace(&self, current: usize, new: usize) -> bool {
+        let is_current = self.get() == current;
+        if is_current {
+            self.set(new);
+        }
+        is_current
+    }
+
+    fn get_mut(&mut self) -> &mut usize {
+        Cell::get_mut(self)
+    }
+}
+
+impl<T> Links<T> {
+    fn new(next: Option<Rc<Node<T>>>) -> Self {
+        Self {
+            next: Cell::new(next),
+            frozen: Cell::new(false),
+            back_link: Cell::new(None),
+        }
+    }
+
+    fn next_dup(&self) -> Option<Rc<Node<T>>> {
+        let next = self.next.take();
+        self.next.set(next.clone());
+        next
+    }
+
+    fn next_is(&self, target: &Node<T>) -> bool {
+        let next = self.next.take();
+        let is = next.as_deref().is_some_and(|next| ptr::eq(next, target));
+        self.next.set(next);
+        is
+    }
+}
+
+impl<T> std::fmt::Debug for Links<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let next = self.next.take();
+        let res = f
+            .debug_struct("Links")
+            .field("next", &next.as_ref().map(Rc::as_ptr))
+            .finish_non_exhaustive();
+        self.next.set(next);
+        res
+    }
+}
+
+impl<T> Drop for Links<T> {
+    /// Frees the nodes owned by this link only one by one, like
+    /// [`Links::release`](crate::cell::Links), so that tearing down a chain
+    /// of any length uses constant stack.
+    fn drop(&mut self) {
+        let mut next = self.next.take();
+        while let Some(node) = next {
+            next = match Rc::try_unwrap(node) {
+                Ok(node) => node.links().and_then(|links| links.next.take()),
+                Err(_shared) => None,
+            };
+        }
+    }
+}
+
+impl<T> Node<T> {
+    fn links(&self) -> Option<&Links<T>> {
+        match self {
+            Node::Data { links, .. } | Node::Aux { links } | Node::First { links } => Some(links),
+            Node::Last => None,
+        }
+    }
+
+    fn back_links(&self) -> Option<&Links<T>> {
+        match self {
+            Node::Data { links, .. } => Some(links),
+            _ => None,
+        }
+    }
+}
+
+impl<T> node::Node<T> for Node<T> {
+    type Strong = Rc<Node<T>>;
+    type State = Cell<usize>;
+    type Hint = WeakLink<T>;
+
+    fn new_detached(data: T) -> Rc<Node<T>> {
+        let aux = Rc::new(Node::Aux {
+            links: Links::new(None),
+        });
+        Rc::new(Node::Data {
+            data: Value::new(data),
+            links: Links::new(Some(aux)),
+        })
+    }
+
+    fn new_aux(next: Rc<Node<T>>) -> Rc<Node<T>> {
+        Rc::new(Node::Aux {
+            links: Links::new(Some(next)),
+        })
+    }
+
+    fn new_first(next: Rc<Node<T>>) -> Rc<Node<T>> {
+        Rc::new(Node::First {
+            links: Links::new(Some(next)),
+        })
+    }
+
+    fn new_last() -> Rc<Node<T>> {
+        Rc::new(Node::Last)
+    }
+
+    fn is_last(&self) -> bool {
+        matches!(self, Node::Last)
+    }
+
+    fn is_data_cell(&self) -> bool {
+        matches!(self, Node::Data { .. })
+    }
+
+    fn is_normal_cell(&self) -> bool {
+        !matches!(self, Node::Aux { .. })
+    }
+
+    fn is_removed(&self) -> bool {
+        self.back_links().is_some_and(|links| {
+            let back_link = links.back_link.take();
+            let is = back_link.is_some();
+            links.back_link.set(back_link);
+            is
+        })
+    }
+
+    fn value(&self) -> Option<&Value<T, Cell<usize>>> {
+        match self {
+            Node::Data { data, .. } => Some(data),
+            _ => None,
+        }
+    }
+
+    fn next_dup(&self) -> Option<Rc<Node<T>>> {
+        self.links()?.next_dup()
+    }
+
+    fn store_next(&self, next: Option<Rc<Node<T>>>) {
+        if let Some(links) = self.links() {
+            links.next.set(next);
+        }
+    }
+
+    fn take_next(&self) -> Option<Rc<Node<T>>> {
+        self.links()?.next.take()
+    }
+
+    fn next_cmp(&self, target: &Node<T>) -> bool {
+        self.links().is_some_and(|links| links.next_is(target))
+    }
+
+    fn swap_in_next(&self, expected: &Node<T>, n: Rc<Node<T>>) -> Result<Rc<Node<T>>> {
+        let links = self.links().ok_or(ListError::CorruptedChain)?;
+        if links.frozen.get() || !links.next_is(expected) {
+            let next = links.next_dup();
+            return Err(ListError::CasFailed {
+                actual: next.map_or(0, |next| Rc::as_ptr(&next) as usize),
+                expected: expected as *const Node<T> as usize,
+            });
+        }
+        Ok(links.next.replace(Some(n)).expect("the expected node is not null"))
+    }
+
+    fn freeze_next(&self, expected: &Node<T>) -> bool {
+        let Some(links) = self.links() else {
+            return false;
+        };
+        if links.frozen.get() || !links.next_is(expected) {
+            return false;
+        }
+        links.frozen.set(true);
+        true
+    }
+
+    fn thaw_next(&self) {
+        if let Some(links) = self.links() {
+            links.frozen.set(false);
+        }
+    }
+
+    fn is_frozen(&self) -> bool {
+        self.links().is_some_and(|links| links.frozen.get())
+    }
+
+    fn backlink_dup(&self) -> Option<Rc<Node<T>>> {
+        let links = self.back_links()?;
+        let back_link = links.back_link.take();
+        let node = back_link.as_ref().and_then(Weak::upgrade);
+        links.back_link.set(back_link);
+        node
+    }
+
+    fn set_backlink(&self, cell: &Rc<Node<T>>) {
+        if let Some(links) = self.back_links() {
+            links.back_link.set(Some(Rc::downgrade(cell)));
+        }
+    }
+
+    fn try_unwrap(this: Rc<Self>) -> std::result::Result<Unwrapped<T, Self>, Rc<Self>> {
+        let node = Rc::try_unwrap(this)?;
+        let next = node.links().and_then(|links| links.next.take());
+        let data = match node {
+            Node::Data { data, .. } => data.into_inner(),
+            _ => None,
+        };
+        Ok((data, next))
+    }
+}
+
+/// Weak link to a node kept outside of the chain, e.g. a position hint.
+pub struct WeakLink<T>(Cell<Option<Weak<Node<T>>>>);
+
+impl<T> std::fmt::Debug for WeakLink<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WeakLink").finish_non_exhaustive()
+    }
+}
+
+impl<T> node::Hint<Rc<Node<T>>> for WeakLink<T> {
+    fn new() -> Self {
+        Self(Cell::new(None))
+    }
+
+    fn store(&self, cell: &Rc<Node<T>>) {
+        self.0.set(Some(Rc::downgrade(cell)));
+    }
+
+    fn clear(&self) {
+        self.0.set(None);
+    }
+
+    fn upgrade(&self) -> Option<Rc<Node<T>>> {
+        let hint = self.0.take();
+        let node = hint.as_ref().and_then(Weak::upgrade);
+        self.0.set(hint);
+        node
+    }
+}
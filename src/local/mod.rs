@@ -0,0 +1,144 @@
+//! Single-threaded lists, for lists which never leave their thread.
+//!
+//! These are the lists of [`list`] with the [`Local`] strategy: the
+//! cursors run the same algorithm on the same kinds of cells, aux cells,
+//! back links and frozen links included, with `Rc` references kept in
+//! `std::cell::Cell`s instead of `Arc` references behind atomic link words,
+//! so no cursor operation touches an atomic.
+//!
+//! Several cursors of one list may be used in turns, like the cursors of
+//! concurrent threads. Nothing else can push while the thread waits,
+//! however: [`List::pop_front_blocking`](crate::list::List::pop_front_blocking)
+//! only returns if the list is not empty, and
+//! [`Removed::into_inner`](crate::list::Removed::into_inner) only if no
+//! [`Ref`] borrows the value. The async pops wait for other tasks of the
+//! thread to push.
+
+use crate::list;
+
+pub use crate::reclaim::Local;
+
+pub(crate) use self::cell::Node;
+
+mod cell;
+
+/// Single-threaded [`list::List`].
+pub type List<T> = list::List<T, Local>;
+
+/// Cursor of a [`List`], see [`list::Cursor`].
+pub type Cursor<T> = list::Cursor<T, Local>;
+
+/// An element removed from a [`List`], see [`list::Removed`].
+pub type Removed<T> = list::Removed<T, Node<T>>;
+
+/// An element of a [`List`], see [`list::Ref`].
+pub type Ref<T> = list::Ref<T, Node<T>>;
+
+pub type Iter<T> = list::Iter<T, Local>;
+pub type IntoIter<T> = list::IntoIter<T, Local>;
+pub type PopStream<'a, T> = list::PopStream<'a, T, Local>;
+pub type InsertError<T> = list::InsertError<T, Node<T>>;
+pub type InsertManyError<T> = list::InsertManyError<T, Node<T>>;
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    use crate::error::ListError;
+
+    use super::List;
+
+    #[test]
+    fn test_push_pop() {
+        let list: List<String> = List::new();
+        assert!(list.is_empty());
+        assert!(list.pop_front().is_none());
+
+        list.push_front("b".to_string());
+        list.push_front("a".to_string());
+        list.push_back("c".to_string());
+        let values: Vec<String> = list.iter().map(|val| (*val).clone()).collect();
+        assert_eq!(values, ["a", "b", "c"]);
+
+        // iterators and cursors do not keep elements from being popped
+        let mut iter = list.iter();
+        assert_eq!(*iter.next().unwrap(), "a");
+        assert_eq!(list.pop_front().as_deref(), Some("a"));
+        assert_eq!(list.pop_back().as_deref(), Some("c"));
+        assert_eq!(*iter.next().unwrap(), "b");
+        drop(iter);
+        let values: Vec<String> = list.into_iter().collect();
+        assert_eq!(values, ["b"]);
+
+        let list: List<u32> = (0..100_000).collect();
+        assert_eq!(list.iter().count(), 100_000);
+        // the chain is freed without recursion
+        drop(list);
+    }
+
+    #[test]
+    fn test_interleaved_cursors() {
+        let list: List<u32> = (0..10).collect();
+
+        let mut reader = list.first().unwrap();
+        reader.advance_while(|&val| val < 5).unwrap();
+        let mut writer = list.first().unwrap();
+        writer.advance_while(|&val| val < 4).unwrap();
+
+        // deletes run the back link and aux compaction of the concurrent list
+        let removed = writer.try_delete().unwrap();
+        assert_eq!(*removed, 4);
+        assert_eq!(writer.try_delete().unwrap_err(), ListError::NeedsUpdate);
+        writer.update().unwrap();
+        assert_eq!(*writer.try_delete().unwrap(), 5);
+
+        // the reader was overtaken and moves on past the removed element
+        assert_eq!(reader.val().as_deref(), Some(&5));
+        let err = reader.try_insert(42).unwrap_err();
+        assert_eq!(err.error(), ListError::NeedsUpdate);
+        assert_eq!(err.into_inner(), 42);
+        reader.next().unwrap();
+        assert_eq!(reader.val().as_deref(), Some(&6));
+        reader.insert(42).unwrap();
+
+        assert_eq!(removed.into_inner(), 4);
+        let values: Vec<u32> = list.iter().map(|val| *val).collect();
+        assert_eq!(values, [0, 1, 2, 3, 42, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let list: List<u32> = (0..10).collect();
+
+        let mut cursor = list.first().unwrap();
+        cursor.advance_while(|&val| val < 2).unwrap();
+        cursor.insert_many([20, 21]).unwrap();
+        let removed = list.first().unwrap().delete_range(3).unwrap();
+        assert_eq!(removed.into_iter().map(|val| val.into_inner()).collect::<Vec<_>>(), [0, 1, 20]);
+
+        // the stale cursor skips the detached run
+        cursor.update().unwrap();
+        assert_eq!(cursor.val().as_deref(), Some(&21));
+        drop(cursor);
+
+        assert_eq!(list.pop_front_many(2), [21, 2]);
+        assert_eq!(list.pop_back(), Some(9));
+        list.push_back(10);
+        assert_eq!(list.take_all().collect::<Vec<_>>(), [3, 4, 5, 6, 7, 8, 10]);
+        assert!(list.is_empty());
+        assert_eq!(list.pop_back(), None);
+    }
+
+    #[test]
+    fn test_pop_front_async() {
+        let list: List<u32> = List::new();
+        let mut cx = Context::from_waker(Waker::noop());
+
+        let mut pop = pin!(list.pop_front_async());
+        assert!(pop.as_mut().poll(&mut cx).is_pending());
+        list.push_back(1);
+        assert_eq!(pop.as_mut().poll(&mut cx), Poll::Ready(1));
+    }
+}
@@ -0,0 +1,91 @@
+//! Operations of the cells a [`Cursor`](crate::list::Cursor) runs on,
+//! either the [`Cell`](crate::cell::Cell)s of concurrent lists or the
+//! `Rc`-based cells of [`local`](crate::local) lists.
+
+use std::ops::Deref;
+
+use crate::cell::{Borrowed, Value};
+use crate::error::Result;
+
+/// Cell of a list: data, aux, first or last, linked to the next cell and,
+/// once deleted, back to its predecessor.
+pub trait Node<T>: Sized {
+    /// Counted reference to a cell, held by links and guards.
+    type Strong: Clone + Deref<Target = Self>;
+    /// Borrow state of the values of data cells.
+    type State: State;
+    /// Weak link to a cell kept outside of the chain.
+    type Hint: Hint<Self::Strong>;
+
+    /// Data cell followed by its aux cell, with the aux not yet linked to
+    /// a successor.
+    fn new_detached(data: T) -> Self::Strong;
+    fn new_aux(next: Self::Strong) -> Self::Strong;
+    fn new_first(next: Self::Strong) -> Self::Strong;
+    fn new_last() -> Self::Strong;
+
+    fn is_last(&self) -> bool;
+    fn is_data_cell(&self) -> bool;
+    fn is_normal_cell(&self) -> bool;
+    /// Whether the data cell has been deleted from its list.
+    fn is_removed(&self) -> bool;
+
+    fn value(&self) -> Option<&Value<T, Self::State>>;
+
+    /// Borrows the value of a data cell, unless it was moved out.
+    fn val(&self) -> Option<Borrowed<'_, T, Self::State>> {
+        self.value()?.borrow()
+    }
+
+    fn next_dup(&self) -> Option<Self::Strong>;
+    fn store_next(&self, next: Option<Self::Strong>);
+    /// Takes the reference of the `next` link out, leaving it null.
+    fn take_next(&self) -> Option<Self::Strong>;
+    fn next_cmp(&self, target: &Self) -> bool;
+
+    /// Swings the `next` link from `expected` to `n` and returns the
+    /// reference the link held to `expected`.
+    fn swap_in_next(&self, expected: &Self, n: Self::Strong) -> Result<Self::Strong>;
+
+    /// Freezes the `next` link while it points at `expected`, so that
+    /// [`Node::swap_in_next`] fails on it until it is thawed; returns
+    /// whether it was frozen.
+    fn freeze_next(&self, expected: &Self) -> bool;
+    fn thaw_next(&self);
+    fn is_frozen(&self) -> bool;
+
+    fn backlink_dup(&self) -> Option<Self::Strong>;
+    /// Links a deleted data cell back to `cell`, its predecessor.
+    fn set_backlink(&self, cell: &Self::Strong);
+
+    /// Unwraps a cell owned by the caller only into its data, if it is a
+    /// data cell, and the cell following it.
+    fn try_unwrap(this: Self::Strong) -> std::result::Result<Unwrapped<T, Self>, Self::Strong>;
+
+    /// Drops a reference which may be the last one.
+    fn recycle(this: Self::Strong) {
+        drop(this);
+    }
+}
+
+/// Data and successor of an unwrapped cell, see [`Node::try_unwrap`].
+pub type Unwrapped<T, C> = (Option<T>, Option<<C as Node<T>>::Strong>);
+
+/// Weak link to a cell, e.g. a position hint of a list.
+pub trait Hint<P> {
+    fn new() -> Self;
+    fn store(&self, cell: &P);
+    fn clear(&self);
+    /// The linked cell, if one was stored and it is still alive.
+    fn upgrade(&self) -> Option<P>;
+}
+
+/// Counter of the borrows of a [`Value`].
+pub trait State: Default {
+    /// Adds `count` and returns the previous state.
+    fn add(&self, count: usize) -> usize;
+    fn sub(&self, count: usize);
+    /// Replaces `current` with `new`; returns whether it was `current`.
+    fn replace(&self, current: usize, new: usize) -> bool;
+    fn get_mut(&mut self) -> &mut usize;
+}
@@ -0,0 +1,317 @@
+//! Per-thread pool of freed cells, off by default.
+//!
+//! Every insert allocates a data cell and an aux cell, and every delete
+//! frees them once their last reference is dropped. With a pool enabled
+//! through [`set_capacity`], a thread keeps the allocations of the cells it
+//! frees and hands them out again to the cells it creates, instead of going
+//! through the allocator each time. Cells are pooled by layout, so lists of
+//! values of the same size share their pooled cells.
+//!
+//! ```
+//! use conc_linklists_std_arc::list::List;
+//! use conc_linklists_std_arc::pool;
+//!
+//! pool::set_capacity(64);
+//! let list: List<i32> = List::new();
+//! for i in 0..100 {
+//!     list.push_front(i);
+//!     list.pop_front();
+//! }
+//! assert!(pool::stats().hit_rate() > 0.9);
+//! pool::set_capacity(0);
+//! ```
+
+use std::alloc::Layout;
+use std::cell::RefCell;
+use std::mem;
+use std::ptr;
+use std::sync::Arc;
+
+use crate::cell::{Cell, Dummy};
+
+/// Counters of the pool of the calling thread, see [`stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Cells created in a pooled allocation.
+    pub hits: u64,
+    /// Cells created with the allocator while the pool was enabled.
+    pub misses: u64,
+    /// Freed cells whose allocation was kept in the pool.
+    pub recycled: u64,
+    /// Freed cells whose allocation was handed back to the allocator
+    /// because the pool was full.
+    pub discarded: u64,
+}
+
+impl PoolStats {
+    /// Share of the cells created in a pooled allocation, 0 if no cell was
+    /// created with the pool enabled.
+    pub fn hit_rate(&self) -> f64 {
+        let created = self.hits + self.misses;
+        if created == 0 {
+            0.0
+        } else {
+            self.hits as f64 / created as f64
+        }
+    }
+}
+
+/// The allocation of a freed `Arc<Cell<T>>`, holding a `Dummy::Last`, with
+/// the type of the cell erased.
+struct Pooled {
+    ptr: *const (),
+    drop: unsafe fn(*const ()),
+}
+
+impl Pooled {
+    fn new<T>(cell: Arc<Cell<T>>) -> Self {
+        unsafe fn drop_arc<T>(ptr: *const ()) {
+            drop(Arc::from_raw(ptr as *const Cell<T>));
+        }
+        debug_assert!(cell.is_last());
+        Self {
+            ptr: Arc::into_raw(cell) as *const (),
+            drop: drop_arc::<T>,
+        }
+    }
+
+    /// Moves `cell` into the allocation.
+    ///
+    /// # Safety
+    ///
+    /// `Cell<T>` must have the layout the allocation was pooled under.
+    unsafe fn reuse<T>(self, cell: Cell<T>) -> Arc<Cell<T>> {
+        let ptr = self.ptr as *mut Cell<T>;
+        mem::forget(self);
+        // the `Dummy::Last` it held owns nothing, so it is overwritten
+        // without being dropped; `Arc::from_raw` accepts a pointer from an
+        // `Arc` of another type with the same size and alignment
+        ptr::write(ptr, cell);
+        Arc::from_raw(ptr)
+    }
+}
+
+impl Drop for Pooled {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` and `drop` come from the same `Arc<Cell<T>>`
+        unsafe { (self.drop)(self.ptr) }
+    }
+}
+
+#[derive(Default)]
+struct Pool {
+    /// Pooled allocations per layout; 0 disables the pool.
+    capacity: usize,
+    bins: Vec<(Layout, Vec<Pooled>)>,
+    stats: PoolStats,
+}
+
+impl Pool {
+    fn bin(&mut self, layout: Layout) -> &mut Vec<Pooled> {
+        let i = match self.bins.iter().position(|(l, _)| *l == layout) {
+            Some(i) => i,
+            None => {
+                self.bins.push((layout, Vec::new()));
+                self.bins.len() - 1
+            }
+        };
+        &mut self.bins[i].1
+    }
+}
+
+thread_local! {
+    static POOL: RefCell<Pool> = RefCell::default();
+}
+
+/// Enables the pool of the calling thread, keeping up to `cells` freed
+/// cells of each layout, or disables it with 0.
+///
+/// Shrinking the pool hands the allocations beyond the new capacity back
+/// to the allocator.
+pub fn set_capacity(cells: usize) {
+    let dropped: Vec<Pooled> = POOL.with(|pool| {
+        let mut pool = pool.borrow_mut();
+        pool.capacity = cells;
+        pool.bins
+            .iter_mut()
+            .flat_map(|(_, bin)| bin.drain(cells.min(bin.len())..))
+            .collect()
+    });
+    drop(dropped);
+}
+
+/// Capacity of the pool of the calling thread, 0 if it is disabled.
+pub fn capacity() -> usize {
+    POOL.with(|pool| pool.borrow().capacity)
+}
+
+/// Counters of the pool of the calling thread.
+pub fn stats() -> PoolStats {
+    POOL.with(|pool| pool.borrow().stats)
+}
+
+/// Resets the counters of the pool of the calling thread.
+pub fn reset_stats() {
+    POOL.with(|pool| pool.borrow_mut().stats = PoolStats::default());
+}
+
+/// Moves `cell` into an allocation of the pool, if there is one, or into
+/// a new one.
+pub(crate) fn alloc<T>(cell: Cell<T>) -> Arc<Cell<T>> {
+    let pooled = POOL.try_with(|pool| {
+        let mut pool = pool.borrow_mut();
+        if pool.capacity == 0 {
+            return None;
+        }
+        let pooled = pool.bin(Layout::new::<Cell<T>>()).pop();
+        match pooled {
+            Some(_) => pool.stats.hits += 1,
+            None => pool.stats.misses += 1,
+        }
+        pooled
+    });
+    match pooled.ok().flatten() {
+        // SAFETY: the allocation is taken from the bin of this layout
+        Some(pooled) => unsafe { pooled.reuse(cell) },
+        None => Arc::new(cell),
+    }
+}
+
+/// Like `Arc::try_unwrap`, keeping the allocation in the pool of the
+/// calling thread if it is enabled and no weak reference is left.
+pub(crate) fn unwrap<T>(mut cell: Arc<Cell<T>>) -> Result<Cell<T>, Arc<Cell<T>>> {
+    let Some(inner) = Arc::get_mut(&mut cell) else {
+        return Arc::try_unwrap(cell);
+    };
+    let value = mem::replace(inner, Cell::Dummy(Dummy::Last(Default::default())));
+    recycle(cell);
+    Ok(value)
+}
+
+/// Drops a reference to a cell, keeping the allocation in the pool if it
+/// was the last one.
+pub(crate) fn release<T>(cell: Arc<Cell<T>>) {
+    // the links of the value release the cells following it
+    let _ = unwrap(cell);
+}
+
+fn recycle<T>(cell: Arc<Cell<T>>) {
+    let mut cell = Some(cell);
+    let _ = POOL.try_with(|pool| {
+        let mut pool = pool.borrow_mut();
+        let capacity = pool.capacity;
+        if capacity == 0 {
+            return;
+        }
+        let bin = pool.bin(Layout::new::<Cell<T>>());
+        if bin.len() < capacity {
+            bin.extend(cell.take().map(Pooled::new));
+            pool.stats.recycled += 1;
+        } else {
+            pool.stats.discarded += 1;
+        }
+    });
+    // a cell not pooled only holds a `Dummy::Last` and is freed here
+    drop(cell);
+}
+
+/// Number of allocations pooled by the calling thread.
+#[cfg(test)]
+pub(crate) fn pooled_count() -> usize {
+    POOL.with(|pool| pool.borrow().bins.iter().map(|(_, bin)| bin.len()).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::list::List;
+    use crate::reclaim::{Counted, Epoch, Hazard, Reclaim};
+
+    use super::*;
+
+    #[test]
+    fn test_push_pop_hits() {
+        set_capacity(16);
+        reset_stats();
+        let list: List<String> = List::new();
+        for i in 0..1_000 {
+            list.push_front(i.to_string());
+            assert_eq!(list.pop_front(), Some(i.to_string()));
+        }
+        let counted = stats();
+        // the aux cell of the new list and the cells of the first push miss
+        assert_eq!(counted.misses, 3);
+        assert_eq!(counted.hits, 2 * 1_000 - 2);
+        assert!(counted.hit_rate() > 0.99);
+        assert!(pooled_count() <= 16);
+
+        // cells of another type with the same layout share the pool
+        assert_eq!(Layout::new::<Cell<Vec<u8>>>(), Layout::new::<Cell<String>>());
+        reset_stats();
+        let other: List<Vec<u8>> = List::new();
+        other.push_front(vec![1]);
+        assert!(stats().hits > 0);
+        drop(other);
+
+        set_capacity(0);
+        assert_eq!(pooled_count(), 0);
+        drop(list);
+    }
+
+    #[test]
+    fn test_capacity_bounds_pool() {
+        set_capacity(8);
+        reset_stats();
+        let list: List<u64> = (0..100).collect();
+        drop(list);
+        let counted = stats();
+        assert_eq!(pooled_count(), 8);
+        assert!(counted.discarded > 0);
+        assert_eq!(counted.recycled, 8);
+
+        set_capacity(0);
+        reset_stats();
+        let list: List<u64> = (0..100).collect();
+        drop(list);
+        assert_eq!(stats(), PoolStats::default());
+    }
+
+    /// Freed cells are reused while other threads still traverse or pop
+    /// around them; with [`Epoch`], a cell may be freed, and pooled, by
+    /// another thread than the one which removed it.
+    fn concurrent_pooled<R: Reclaim<usize> + 'static>()
+    where
+        List<usize, R>: Send + Sync,
+    {
+        let list: Arc<List<usize, R>> = Arc::new(List::new());
+        let jhs: Vec<_> = (0..4)
+            .map(|t| {
+                let list = Arc::clone(&list);
+                thread::spawn(move || {
+                    set_capacity(32);
+                    for i in 0..2_000 {
+                        list.push_front(t * 2_000 + i);
+                        assert!(list.pop_front().is_some());
+                        if i % 100 == 0 {
+                            assert!(list.iter().count() <= 4);
+                        }
+                    }
+                    let counted = stats();
+                    set_capacity(0);
+                    counted
+                })
+            })
+            .collect();
+        let hits: u64 = jhs.into_iter().map(|jh| jh.join().unwrap().hits).sum();
+        assert!(hits > 0);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_pooled() {
+        concurrent_pooled::<Counted>();
+        concurrent_pooled::<Epoch>();
+        concurrent_pooled::<Hazard>();
+    }
+}
@@ -0,0 +1,116 @@
+use crate::list::{Iter, List};
+
+/// Lock-free FIFO queue on top of [`List`].
+///
+/// Values are enqueued at the back with [`List::push_back`] and dequeued
+/// from the front with [`List::pop_front`].
+#[derive(Debug)]
+pub struct Queue<T> {
+    list: List<T>,
+}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        Self { list: List::new() }
+    }
+
+    /// Iterates over the values from the head to the tail of the queue;
+    /// the iteration is weakly consistent, see [`Iter`].
+    pub fn iter(&self) -> Iter<T> {
+        self.list.iter()
+    }
+
+    pub fn enqueue(&self, value: T) {
+        self.list.push_back(value);
+    }
+
+    /// Removes the value at the head of the queue; see [`List::pop_front`].
+    pub fn dequeue(&self) -> Option<T> {
+        self.list.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use super::Queue;
+
+    #[test]
+    fn test_fifo() {
+        let queue = Queue::new();
+        assert_eq!(queue.dequeue(), None);
+
+        for i in 0..100 {
+            queue.enqueue(i);
+        }
+        for i in 0..50 {
+            assert_eq!(queue.dequeue(), Some(i));
+        }
+        queue.enqueue(100);
+        assert_eq!(
+            queue.iter().map(|val| *val).collect::<Vec<_>>(),
+            (50..=100).collect::<Vec<_>>()
+        );
+        for i in 50..=100 {
+            assert_eq!(queue.dequeue(), Some(i));
+        }
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn test_concurrent_queue() {
+        let queue: Arc<Queue<(usize, usize)>> = Arc::new(Queue::new());
+
+        const PRODUCERS: usize = 4;
+        const CONSUMERS: usize = 4;
+        const ITER: usize = 2000;
+
+        let mut producers = vec![];
+        for t in 0..PRODUCERS {
+            let queue_copy = Arc::clone(&queue);
+            producers.push(thread::spawn(move || {
+                for i in 0..ITER {
+                    queue_copy.enqueue((t, i));
+                }
+            }));
+        }
+        let mut consumers = vec![];
+        for _ in 0..CONSUMERS {
+            let queue_copy = Arc::clone(&queue);
+            consumers.push(thread::spawn(move || {
+                let mut dequeued = vec![];
+                while dequeued.len() < PRODUCERS * ITER / CONSUMERS {
+                    if let Some(val) = queue_copy.dequeue() {
+                        dequeued.push(val);
+                    }
+                }
+                dequeued
+            }));
+        }
+        for jh in producers {
+            jh.join().unwrap();
+        }
+
+        let mut all = vec![];
+        for jh in consumers {
+            let dequeued = jh.join().unwrap();
+            // every consumer sees the values of a producer in FIFO order
+            for t in 0..PRODUCERS {
+                let seen: Vec<usize> = dequeued.iter().filter(|val| val.0 == t).map(|val| val.1).collect();
+                assert!(seen.windows(2).all(|pair| pair[0] < pair[1]));
+            }
+            all.extend(dequeued);
+        }
+        all.sort_unstable();
+        let expected: Vec<(usize, usize)> = (0..PRODUCERS).flat_map(|t| (0..ITER).map(move |i| (t, i))).collect();
+        assert_eq!(all, expected);
+        assert_eq!(queue.dequeue(), None);
+    }
+}
@@ -0,0 +1,473 @@
+//! Reclamation strategies of the cells of a [`List`](crate::list::List).
+//!
+//! Cells are reference counted and `next` links own a reference to their
+//! target in every strategy. The strategies differ in how cursors hold the
+//! cells they traverse and in when the reference of a link is dropped after
+//! the link was swung away from its cell:
+//!
+//! - [`Counted`] cursors clone an `Arc` at every step, and swapped out
+//!   references are dropped at once;
+//! - [`Epoch`] cursors pin the thread once and follow raw `next` pointers,
+//!   and swapped out references are dropped once every thread pinned at
+//!   the time has unpinned;
+//! - [`Hazard`] cursors publish each cell they reference in a hazard
+//!   pointer, and swapped out references are dropped once no hazard pointer
+//!   publishes their cell.
+//!
+//! [`Local`] lists never leave their thread, and link `Rc`-based cells
+//! instead; see [`local`](crate::local).
+
+use std::ptr::{self, NonNull};
+use std::sync::Arc;
+
+use crate::cell::Cell;
+use crate::node::Node;
+
+/// Reclamation strategy of the cells of a [`List`](crate::list::List),
+/// either [`Counted`], [`Epoch`] or [`Hazard`], or [`Local`] for lists
+/// which never leave their thread.
+pub trait Reclaim<T>: backend::Backend<T> {}
+
+impl<T, R: backend::Backend<T>> Reclaim<T> for R {}
+
+/// Cursors hold an `Arc` of each cell they reference, cloned with two
+/// atomic read-modify-write operations at every step.
+///
+/// Cells are freed as soon as the last reference is dropped.
+#[derive(Debug)]
+pub enum Counted {}
+
+/// Cursors pin the thread in the global epoch of `crossbeam-epoch` once and
+/// follow raw `next` pointers, without touching reference counts.
+///
+/// References dropped from `next` links are deferred until every thread
+/// pinned at the time has unpinned, so cells stay allocated for as long as a
+/// pinned cursor may reach them. A thread holding a cursor or an iterator
+/// therefore delays the reclamation of every cell unlinked meanwhile, and
+/// must drop it before calling
+/// [`Removed::into_inner`](crate::list::Removed::into_inner) on such a cell.
+#[derive(Debug)]
+pub enum Epoch {}
+
+/// Cursors publish each cell they reference in a hazard pointer, validated
+/// with one more load of the link it was read from, instead of counting a
+/// reference.
+///
+/// References dropped from `next` links are retired until no hazard pointer
+/// publishes their cell. Unlike with [`Epoch`], a stalled thread only keeps
+/// the few cells its cursors reference from being reclaimed, so garbage
+/// stays bounded; the price is a store and a load per step, and a scan of
+/// the hazard pointers when cells are freed.
+#[derive(Debug)]
+pub enum Hazard {}
+
+/// Cells are `Rc`s linked through `std::cell::Cell`s, for lists which
+/// never leave their thread, see [`local`](crate::local).
+///
+/// Cursors clone an `Rc` at every step, and cells are freed as soon as the
+/// last reference is dropped.
+#[derive(Debug)]
+pub enum Local {}
+
+pub(crate) mod backend {
+    use std::ops::Deref;
+
+    use super::*;
+    use std::rc::Rc;
+
+    use crate::hazard::{self, Slot};
+    use crate::local;
+    use crate::pool;
+
+    /// Counted reference to a cell of a list with strategy `R`.
+    pub type Strong<T, R> = <<R as Backend<T>>::Cell as Node<T>>::Strong;
+
+    /// Operations of a strategy, hidden behind the sealed [`Reclaim`].
+    pub trait Backend<T>: Sized {
+        /// Cells of the lists with this strategy.
+        type Cell: Node<T>;
+        /// A cell referenced by a cursor.
+        type Ptr: Clone + Deref<Target = Self::Cell>;
+        /// Kept by a cursor for as long as it references cells.
+        type Guard;
+
+        /// Starts a traversal at `root`, which the guard keeps alive.
+        fn pin(root: &Strong<T, Self>) -> (Self::Guard, Self::Ptr);
+
+        /// The cell following `cell`, `None` for the last cell.
+        fn next(cell: &Self::Ptr, guard: &Self::Guard) -> Option<Self::Ptr>;
+
+        /// References a cell the caller holds a counted reference of.
+        fn from_strong(cell: Strong<T, Self>) -> Self::Ptr;
+
+        /// A counted reference to `cell`, e.g. to be stored in a link.
+        fn to_strong(cell: &Self::Ptr) -> Strong<T, Self>;
+
+        /// Drops the reference a `next` link held before it was swung away.
+        fn retire(cell: Strong<T, Self>, guard: &Self::Guard);
+
+        /// Prepares the detached run of cells from `head` to `tail` to be
+        /// published in a list with this strategy.
+        fn adopt(_head: &Self::Cell, _tail: &Self::Cell) {}
+
+        /// Drops a reference of a cursor which is done with the list.
+        fn release(cell: Self::Ptr) {
+            drop(cell);
+        }
+    }
+
+    impl<T> Backend<T> for Counted {
+        type Cell = Cell<T>;
+        type Ptr = Arc<Cell<T>>;
+        type Guard = ();
+
+        fn pin(root: &Arc<Cell<T>>) -> ((), Arc<Cell<T>>) {
+            ((), root.clone())
+        }
+
+        fn next(cell: &Arc<Cell<T>>, _guard: &()) -> Option<Arc<Cell<T>>> {
+            cell.next_dup()
+        }
+
+        fn from_strong(cell: Arc<Cell<T>>) -> Arc<Cell<T>> {
+            cell
+        }
+
+        fn to_strong(cell: &Arc<Cell<T>>) -> Arc<Cell<T>> {
+            cell.clone()
+        }
+
+        fn retire(cell: Arc<Cell<T>>, _guard: &()) {
+            pool::release(cell);
+        }
+
+        fn release(cell: Arc<Cell<T>>) {
+            pool::release(cell);
+        }
+    }
+
+    impl<T> Backend<T> for Local {
+        type Cell = local::Node<T>;
+        type Ptr = Rc<local::Node<T>>;
+        type Guard = ();
+
+        fn pin(root: &Rc<local::Node<T>>) -> ((), Rc<local::Node<T>>) {
+            ((), root.clone())
+        }
+
+        fn next(cell: &Rc<local::Node<T>>, _guard: &()) -> Option<Rc<local::Node<T>>> {
+            cell.next_dup()
+        }
+
+        fn from_strong(cell: Rc<local::Node<T>>) -> Rc<local::Node<T>> {
+            cell
+        }
+
+        fn to_strong(cell: &Rc<local::Node<T>>) -> Rc<local::Node<T>> {
+            cell.clone()
+        }
+
+        fn retire(cell: Rc<local::Node<T>>, _guard: &()) {
+            drop(cell);
+        }
+    }
+
+    /// Pinned traversal, with the `Arc` of the cell it started at.
+    #[derive(Debug)]
+    pub struct EpochGuard<T> {
+        pin: crossbeam_epoch::Guard,
+        _root: Arc<Cell<T>>,
+    }
+
+    /// A cell referenced by a cursor of an [`Epoch`] list.
+    #[derive(Debug)]
+    pub enum EpochPtr<T> {
+        /// Reached through `next` links from the root of the traversal;
+        /// valid while its guard is held.
+        Pinned(NonNull<Cell<T>>),
+        /// Reached through a back link, or from such a cell. A cell owned by
+        /// an `Owned` pointer only may be freed, and release its `next` link
+        /// immediately, once the pointer is dropped, so the cells following
+        /// it are owned too.
+        Owned(Arc<Cell<T>>),
+    }
+
+    impl<T> Clone for EpochPtr<T> {
+        fn clone(&self) -> Self {
+            match self {
+                EpochPtr::Pinned(ptr) => EpochPtr::Pinned(*ptr),
+                EpochPtr::Owned(cell) => EpochPtr::Owned(cell.clone()),
+            }
+        }
+    }
+
+    impl<T> Deref for EpochPtr<T> {
+        type Target = Cell<T>;
+
+        fn deref(&self) -> &Cell<T> {
+            match self {
+                // SAFETY: only cursors hold pinned pointers, together with
+                // the guard which keeps the cell from being freed
+                EpochPtr::Pinned(ptr) => unsafe { ptr.as_ref() },
+                EpochPtr::Owned(cell) => cell,
+            }
+        }
+    }
+
+    impl<T: Send + Sync + 'static> Backend<T> for Epoch {
+        type Cell = Cell<T>;
+        type Ptr = EpochPtr<T>;
+        type Guard = EpochGuard<T>;
+
+        fn pin(root: &Arc<Cell<T>>) -> (EpochGuard<T>, EpochPtr<T>) {
+            let ptr = NonNull::new(Arc::as_ptr(root) as *mut Cell<T>).expect("arcs are not null");
+            let guard = EpochGuard {
+                pin: crossbeam_epoch::pin(),
+                _root: root.clone(),
+            };
+            (guard, EpochPtr::Pinned(ptr))
+        }
+
+        fn next(cell: &EpochPtr<T>, _guard: &EpochGuard<T>) -> Option<EpochPtr<T>> {
+            match cell {
+                EpochPtr::Pinned(_) => NonNull::new(cell.load_next()).map(EpochPtr::Pinned),
+                EpochPtr::Owned(cell) => cell.next_dup().map(EpochPtr::Owned),
+            }
+        }
+
+        fn from_strong(cell: Arc<Cell<T>>) -> EpochPtr<T> {
+            EpochPtr::Owned(cell)
+        }
+
+        fn to_strong(cell: &EpochPtr<T>) -> Arc<Cell<T>> {
+            match cell {
+                EpochPtr::Pinned(ptr) => {
+                    let ptr = ptr.as_ptr() as *const Cell<T>;
+                    // SAFETY: the pointer was loaded from a link, which
+                    // stores pointers from `Arc::into_raw`, and the cell is
+                    // kept alive by the guard
+                    unsafe {
+                        Arc::increment_strong_count(ptr);
+                        Arc::from_raw(ptr)
+                    }
+                }
+                EpochPtr::Owned(cell) => cell.clone(),
+            }
+        }
+
+        fn retire(cell: Arc<Cell<T>>, guard: &EpochGuard<T>) {
+            guard.pin.defer(move || pool::release(cell));
+        }
+    }
+
+    /// A cell referenced by a cursor of a [`Hazard`] list.
+    #[derive(Debug)]
+    pub enum HazardPtr<T> {
+        /// Read from a `next` link and published in `slot`.
+        Published {
+            cell: NonNull<Cell<T>>,
+            slot: &'static Slot,
+        },
+        /// The root of a traversal, or reached through a back link.
+        Owned(Arc<Cell<T>>),
+    }
+
+    impl<T> Clone for HazardPtr<T> {
+        fn clone(&self) -> Self {
+            match self {
+                HazardPtr::Published { cell, .. } => {
+                    // the cell stays published by `self` meanwhile
+                    let slot = hazard::acquire();
+                    slot.publish(cell.as_ptr());
+                    HazardPtr::Published { cell: *cell, slot }
+                }
+                HazardPtr::Owned(cell) => HazardPtr::Owned(cell.clone()),
+            }
+        }
+    }
+
+    impl<T> Drop for HazardPtr<T> {
+        fn drop(&mut self) {
+            if let HazardPtr::Published { slot, .. } = self {
+                slot.release();
+            }
+        }
+    }
+
+    impl<T> Deref for HazardPtr<T> {
+        type Target = Cell<T>;
+
+        fn deref(&self) -> &Cell<T> {
+            match self {
+                // SAFETY: a published cell is not freed, see `hazard`
+                HazardPtr::Published { cell, .. } => unsafe { cell.as_ref() },
+                HazardPtr::Owned(cell) => cell,
+            }
+        }
+    }
+
+    impl<T: Send + Sync + 'static> Backend<T> for Hazard {
+        type Cell = Cell<T>;
+        type Ptr = HazardPtr<T>;
+        type Guard = ();
+
+        fn pin(root: &Arc<Cell<T>>) -> ((), HazardPtr<T>) {
+            ((), HazardPtr::Owned(root.clone()))
+        }
+
+        fn next(cell: &HazardPtr<T>, _guard: &()) -> Option<HazardPtr<T>> {
+            let slot = hazard::acquire();
+            match NonNull::new(cell.load_next_published(|ptr| slot.publish(ptr))) {
+                Some(next) => Some(HazardPtr::Published { cell: next, slot }),
+                None => {
+                    slot.release();
+                    None
+                }
+            }
+        }
+
+        fn from_strong(cell: Arc<Cell<T>>) -> HazardPtr<T> {
+            HazardPtr::Owned(cell)
+        }
+
+        fn to_strong(cell: &HazardPtr<T>) -> Arc<Cell<T>> {
+            match cell {
+                HazardPtr::Published { cell, .. } => {
+                    let ptr = cell.as_ptr() as *const Cell<T>;
+                    // SAFETY: the pointer was loaded from a link, which
+                    // stores pointers from `Arc::into_raw`, and the cell is
+                    // published
+                    unsafe {
+                        Arc::increment_strong_count(ptr);
+                        Arc::from_raw(ptr)
+                    }
+                }
+                HazardPtr::Owned(cell) => cell.clone(),
+            }
+        }
+
+        fn retire(cell: Arc<Cell<T>>, _guard: &()) {
+            hazard::retire(cell);
+        }
+
+        fn adopt(head: &Cell<T>, tail: &Cell<T>) {
+            let mut cell = head;
+            while !ptr::eq(cell, tail) {
+                cell.mark_hazard();
+                // SAFETY: the run is owned by the caller
+                cell = unsafe { &*cell.load_next() };
+            }
+            tail.mark_hazard();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use super::{Counted, Epoch, Hazard, Reclaim};
+    use crate::hazard;
+    use crate::list::List;
+
+    fn push_pop_iter<R: Reclaim<usize> + 'static>()
+    where
+        List<usize, R>: Send + Sync,
+    {
+        let list: Arc<List<usize, R>> = Arc::new(List::new());
+
+        const NUM_THREADS: usize = 4;
+        const ITER: usize = 1000;
+
+        let mut jhs = vec![];
+        for _ in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                for i in 0..ITER {
+                    list_copy.push_front(i);
+                    list_copy.push_back(i);
+                    assert!(list_copy.pop_front().is_some());
+                }
+            }));
+        }
+        for _ in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                for _ in 0..10 {
+                    let sum: usize = list_copy.iter().map(|val| *val).sum();
+                    assert!(sum <= NUM_THREADS * ITER * ITER);
+                }
+            }));
+        }
+        for jh in jhs {
+            jh.join().unwrap();
+        }
+        assert_eq!(list.iter().count(), NUM_THREADS * ITER);
+        assert_eq!(list.take_all().count(), NUM_THREADS * ITER);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_counted() {
+        push_pop_iter::<Counted>();
+    }
+
+    #[test]
+    fn test_concurrent_epoch() {
+        push_pop_iter::<Epoch>();
+    }
+
+    #[test]
+    fn test_concurrent_hazard() {
+        push_pop_iter::<Hazard>();
+    }
+
+    #[test]
+    fn test_hazard_stalled_reader() {
+        let list: Arc<List<String, Hazard>> = Arc::new((0..10).map(|i| i.to_string()).collect());
+        // a reader stalled inside the list keeps its cells only
+        let mut stalled = list.iter();
+        assert_eq!(*stalled.next().unwrap(), "0");
+
+        let list_copy = Arc::clone(&list);
+        let max_retired = thread::spawn(move || {
+            let mut max_retired = 0;
+            for i in 0..10_000 {
+                list_copy.push_front(i.to_string());
+                list_copy.push_back(i.to_string());
+                assert_eq!(list_copy.pop_front(), Some(i.to_string()));
+                max_retired = max_retired.max(hazard::retired_count());
+            }
+            max_retired
+        })
+        .join()
+        .unwrap();
+        assert!(max_retired < 1000, "{} cells retired", max_retired);
+
+        assert_eq!(*stalled.next().unwrap(), "1");
+        drop(stalled);
+        assert_eq!(list.pop_front().as_deref(), Some("0"));
+        assert_eq!(list.take_all().count(), 9 + 10_000);
+    }
+
+    #[test]
+    fn test_epoch_pinned_iter() {
+        let list: List<String, Epoch> = (0..10).map(|i| i.to_string()).collect();
+        let mut iter = list.iter();
+        assert_eq!(*iter.next().unwrap(), "0");
+
+        // cells unlinked while the iterator is pinned stay readable
+        let removed = list.first().unwrap().delete_range(3).unwrap();
+        // pops do not wait for the pinned iterator of the same thread
+        let popped = list.pop_front().unwrap();
+        assert_eq!(popped, "3");
+        let taken = list.take_all();
+        drop(taken);
+        assert_eq!(*iter.next().unwrap(), "1");
+        assert!(iter.all(|val| val.parse::<u32>().is_ok()));
+        // the values are moved out once no pinned traversal can reach them
+        drop(iter);
+        assert_eq!(removed.into_iter().map(|val| val.into_inner()).collect::<Vec<_>>(), ["0", "1", "2"]);
+        assert!(list.is_empty());
+    }
+}
@@ -0,0 +1,491 @@
+use std::collections::hash_map::RandomState;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hasher};
+use std::ops::{Bound, Deref, RangeBounds, RangeFull};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::cell::Cell;
+use crate::error::ListError;
+use crate::list::{Cursor, List, Ref};
+
+/// Number of levels of a [`SkipList`].
+const MAX_HEIGHT: usize = 24;
+
+/// Lock-free ordered map with expected O(log n) operations.
+///
+/// The bottom level is a [`List`] of entries in key order; every level
+/// above is a list of index nodes pointing at a node of the level below.
+/// Entries are linked and removed at the bottom level with the usual
+/// single-CAS cursor operations, towers are built afterwards and index
+/// nodes of removed entries are unlinked lazily by later traversals.
+#[derive(Debug)]
+pub struct SkipList<K, V> {
+    levels: Box<[List<Node<K, V>>]>,
+    /// Number of levels with index nodes, at least 1.
+    height: AtomicUsize,
+    len: AtomicUsize,
+}
+
+#[derive(Debug)]
+enum Node<K, V> {
+    Entry {
+        key: K,
+        value: V,
+    },
+    Index {
+        entry: NodeCell<K, V>,
+        down: NodeCell<K, V>,
+    },
+}
+
+type NodeCell<K, V> = Arc<Cell<Node<K, V>>>;
+
+impl<K, V> Node<K, V> {
+    fn key(&self) -> &K {
+        match self {
+            Node::Entry { key, .. } => key,
+            Node::Index { entry, .. } => node_of(entry).key(),
+        }
+    }
+
+    /// Whether this is an index node of a removed entry.
+    fn is_stale(&self) -> bool {
+        matches!(self, Node::Index { entry, .. } if entry.is_removed())
+    }
+}
+
+fn node_of<K, V>(cell: &NodeCell<K, V>) -> &Node<K, V> {
+    let value = cell.value().expect("nodes are data cells");
+    // SAFETY: nodes are never moved out of their cells, removals hand out
+    // `Ref`s only
+    unsafe { value.get() }
+}
+
+/// Height of a new tower; every level is kept with probability 1/2.
+fn random_height() -> usize {
+    thread_local! {
+        static STATE: std::cell::Cell<u64> =
+            std::cell::Cell::new(RandomState::new().build_hasher().finish() | 1);
+    }
+    STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        state.set(x);
+        (x.trailing_ones() as usize + 1).min(MAX_HEIGHT)
+    })
+}
+
+/// Entry of a [`SkipList`], kept alive while the guard is held.
+pub struct EntryRef<K, V> {
+    entry: Ref<Node<K, V>>,
+}
+
+impl<K, V> EntryRef<K, V> {
+    pub fn key(&self) -> &K {
+        self.entry.key()
+    }
+}
+
+impl<K, V> Clone for EntryRef<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            entry: self.entry.clone(),
+        }
+    }
+}
+
+impl<K, V> Deref for EntryRef<K, V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        match *self.entry {
+            Node::Entry { ref value, .. } => value,
+            Node::Index { .. } => unreachable!("entry refs point to bottom level nodes"),
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for EntryRef<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("EntryRef")
+            .field(self.key())
+            .field(&**self)
+            .finish()
+    }
+}
+
+/// Iterator over a key range of a [`SkipList`], see [`SkipList::range`].
+///
+/// The iteration is weakly consistent: it never yields an entry twice or
+/// out of order, but may miss entries inserted or removed concurrently.
+pub struct Range<K, V, R> {
+    cursor: Cursor<Node<K, V>>,
+    range: R,
+}
+
+impl<K: Ord, V, R: RangeBounds<K>> Iterator for Range<K, V, R> {
+    type Item = EntryRef<K, V>;
+
+    fn next(&mut self) -> Option<EntryRef<K, V>> {
+        let cell = self.cursor.target_data()?.clone();
+        let key = node_of(&cell).key();
+        let past_end = match self.range.end_bound() {
+            Bound::Included(end) => key > end,
+            Bound::Excluded(end) => key >= end,
+            Bound::Unbounded => false,
+        };
+        if past_end {
+            return None;
+        }
+        self.cursor.next().expect("list chain is corrupted");
+        Some(EntryRef {
+            entry: Ref::new(cell).expect("nodes are never moved out"),
+        })
+    }
+}
+
+impl<K, V> Default for SkipList<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> SkipList<K, V> {
+    pub fn new() -> Self {
+        Self {
+            levels: (0..MAX_HEIGHT).map(|_| List::new()).collect(),
+            height: AtomicUsize::new(1),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of entries; approximate under concurrent modification.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Ord, V> SkipList<K, V> {
+    /// Advances the cursor to the first node with a key not less than
+    /// `key`, unlinking index nodes of removed entries on the way.
+    fn advance(cursor: &mut Cursor<Node<K, V>>, key: &K) {
+        loop {
+            cursor
+                .advance_while(|node| node.key() < key && !node.is_stale())
+                .expect("list chain is corrupted");
+            if !cursor.val().is_some_and(|node| node.is_stale()) {
+                return;
+            }
+            match cursor.try_delete() {
+                Ok(_) | Err(ListError::NeedsUpdate) => {
+                    cursor.update().expect("list chain is corrupted")
+                }
+                Err(err) => panic!("list chain is corrupted: {}", err),
+            }
+        }
+    }
+
+    /// Cursors at the first node with a key not less than `key` on every
+    /// level in use, bottom level first.
+    fn search(&self, key: &K) -> Vec<Cursor<Node<K, V>>> {
+        let height = self.height.load(Ordering::Acquire);
+        let mut cursors = Vec::with_capacity(height);
+        let mut cursor = self.levels[height - 1]
+            .first()
+            .expect("list chain is corrupted");
+        for level in (0..height).rev() {
+            Self::advance(&mut cursor, key);
+            let down = match cursor.pre_cell().val().as_deref() {
+                Some(Node::Index { down, .. }) => Some(down.clone()),
+                _ => None,
+            };
+            cursors.push(cursor);
+            if level == 0 {
+                break;
+            }
+            // descend from the last node before `key`, or from the front
+            cursor = match down {
+                Some(down) => Cursor::after(&down),
+                None => self.levels[level - 1].first(),
+            }
+            .expect("list chain is corrupted");
+        }
+        cursors.reverse();
+        cursors
+    }
+
+    /// Entry the cursor is positioned at, if its key is `key`.
+    fn found(cursor: &Cursor<Node<K, V>>, key: &K) -> Option<NodeCell<K, V>> {
+        cursor
+            .target_data()
+            .filter(|target| node_of(target).key() == key)
+            .cloned()
+    }
+
+    /// Unlinks the index nodes of removed entries under `key` from all
+    /// levels.
+    fn unlink_stale(&self, key: &K) {
+        for mut cursor in self.search(key).into_iter().skip(1) {
+            while let Some(stale) = cursor
+                .val()
+                .filter(|node| node.key() == key)
+                .map(|node| node.is_stale())
+            {
+                if !stale {
+                    cursor.next().expect("list chain is corrupted");
+                    continue;
+                }
+                match cursor.try_delete() {
+                    Ok(_) | Err(ListError::NeedsUpdate) => {
+                        cursor.update().expect("list chain is corrupted")
+                    }
+                    Err(err) => panic!("list chain is corrupted: {}", err),
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<EntryRef<K, V>> {
+        let cursor = self.search(key).swap_remove(0);
+        Self::found(&cursor, key).map(|cell| EntryRef {
+            entry: Ref::new(cell).expect("nodes are never moved out"),
+        })
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        Self::found(&self.search(key).swap_remove(0), key).is_some()
+    }
+
+    /// Inserts `value` under `key`; returns `false` and drops `value` if
+    /// the key is present.
+    pub fn insert(&self, key: K, value: V) -> bool {
+        let entry = Cell::new_detached(Node::Entry { key, value });
+        let key = node_of(&entry).key();
+
+        let mut cursors = self.search(key);
+        loop {
+            if Self::found(&cursors[0], key).is_some() {
+                return false;
+            }
+            match cursors[0].try_insert_cell(entry.clone()) {
+                Ok(()) => break,
+                Err(err) if err.error() == ListError::NeedsUpdate => {
+                    cursors[0].update().expect("list chain is corrupted");
+                    Self::advance(&mut cursors[0], key);
+                }
+                Err(err) => panic!("list chain is corrupted: {}", err.error()),
+            }
+        }
+        self.len.fetch_add(1, Ordering::Relaxed);
+
+        let height = random_height();
+        self.height.fetch_max(height, Ordering::AcqRel);
+        let mut upper = cursors.into_iter().skip(1);
+        let mut down = entry.clone();
+        'tower: for level in 1..height {
+            let mut cursor = match upper.next() {
+                Some(cursor) => cursor,
+                None => {
+                    let mut cursor = self.levels[level].first().expect("list chain is corrupted");
+                    Self::advance(&mut cursor, key);
+                    cursor
+                }
+            };
+            let index = Cell::new_detached(Node::Index {
+                entry: entry.clone(),
+                down,
+            });
+            loop {
+                // the tower of a removed entry is not built any further
+                if entry.is_removed() {
+                    break 'tower;
+                }
+                match cursor.try_insert_cell(index.clone()) {
+                    Ok(()) => break,
+                    Err(err) if err.error() == ListError::NeedsUpdate => {
+                        cursor.update().expect("list chain is corrupted");
+                        Self::advance(&mut cursor, key);
+                    }
+                    Err(err) => panic!("list chain is corrupted: {}", err.error()),
+                }
+            }
+            down = index;
+        }
+        // the remover may have missed index nodes linked after its cleanup
+        if entry.is_removed() {
+            self.unlink_stale(key);
+        }
+        true
+    }
+
+    /// Removes the entry under `key` and returns it.
+    pub fn remove(&self, key: &K) -> Option<EntryRef<K, V>> {
+        let mut cursor = self.search(key).swap_remove(0);
+        loop {
+            Self::found(&cursor, key)?;
+            match cursor.try_delete() {
+                Ok(removed) => {
+                    drop(cursor);
+                    self.len.fetch_sub(1, Ordering::Relaxed);
+                    self.unlink_stale(key);
+                    return Some(EntryRef {
+                        entry: removed.into(),
+                    });
+                }
+                Err(ListError::NeedsUpdate) => {
+                    cursor.update().expect("list chain is corrupted");
+                    Self::advance(&mut cursor, key);
+                }
+                Err(err) => panic!("list chain is corrupted: {}", err),
+            }
+        }
+    }
+
+    /// Iterates over the entries with keys in `range`, in key order.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Range<K, V, R> {
+        let mut cursor = match range.start_bound() {
+            Bound::Included(start) | Bound::Excluded(start) => self.search(start).swap_remove(0),
+            Bound::Unbounded => self.levels[0].first().expect("list chain is corrupted"),
+        };
+        if let Bound::Excluded(start) = range.start_bound() {
+            cursor
+                .advance_while(|node| node.key() <= start)
+                .expect("list chain is corrupted");
+        }
+        Range { cursor, range }
+    }
+
+    /// Iterates over all entries in key order.
+    pub fn iter(&self) -> Range<K, V, RangeFull> {
+        self.range(..)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Bound, RangeBounds};
+    use std::sync::atomic::Ordering;
+    use std::{sync::Arc, thread};
+
+    use super::{Range, SkipList};
+
+    fn entries(list: &SkipList<u32, u32>) -> Vec<(u32, u32)> {
+        list.iter().map(|entry| (*entry.key(), *entry)).collect()
+    }
+
+    fn keys<R: RangeBounds<u32>>(range: Range<u32, u32, R>) -> Vec<u32> {
+        range.map(|entry| *entry.key()).collect()
+    }
+
+    /// Number of index nodes on the levels above the bottom one.
+    fn index_nodes(list: &SkipList<u32, u32>) -> usize {
+        list.levels[1..]
+            .iter()
+            .map(|level| {
+                let mut cursor = level.first().unwrap();
+                let mut count = 0;
+                while cursor.val().is_some() {
+                    count += 1;
+                    cursor.next().unwrap();
+                }
+                count
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_skiplist_operations() {
+        let list = SkipList::new();
+
+        for i in [5, 1, 9, 3, 7] {
+            assert!(list.insert(i, i * 10));
+        }
+        assert!(!list.insert(3, 0));
+        assert_eq!(list.len(), 5);
+        assert_eq!(
+            entries(&list),
+            vec![(1, 10), (3, 30), (5, 50), (7, 70), (9, 90)]
+        );
+
+        assert_eq!(*list.get(&7).unwrap(), 70);
+        assert!(list.get(&8).is_none());
+        assert!(list.contains_key(&1));
+
+        assert_eq!(keys(list.range(3..7)), vec![3, 5]);
+        assert_eq!(keys(list.range(3..=7)), vec![3, 5, 7]);
+        assert_eq!(keys(list.range(4..)), vec![5, 7, 9]);
+        assert_eq!(
+            keys(list.range((Bound::Excluded(3), Bound::Unbounded))),
+            vec![5, 7, 9]
+        );
+
+        let removed = list.remove(&5).unwrap();
+        assert_eq!((*removed.key(), *removed), (5, 50));
+        assert!(list.remove(&5).is_none());
+        assert!(!list.contains_key(&5));
+        assert_eq!(entries(&list), vec![(1, 10), (3, 30), (7, 70), (9, 90)]);
+    }
+
+    #[test]
+    fn test_skiplist_towers() {
+        let list = SkipList::new();
+        for i in 0..1000 {
+            list.insert(i, i);
+        }
+        assert!(list.height.load(Ordering::Relaxed) > 1);
+        assert!(index_nodes(&list) > 0);
+        assert_eq!(entries(&list), (0..1000).map(|i| (i, i)).collect::<Vec<_>>());
+
+        for i in 0..1000 {
+            assert_eq!(*list.remove(&i).unwrap(), i);
+        }
+        assert!(list.is_empty());
+        assert_eq!(index_nodes(&list), 0);
+    }
+
+    #[test]
+    fn test_concurrent_skiplist() {
+        let list: Arc<SkipList<u32, u32>> = Arc::new(SkipList::new());
+
+        const NUM_THREADS: u32 = 16;
+        const KEYS: u32 = 500;
+
+        let mut jhs = vec![];
+        for t in 0..NUM_THREADS {
+            let list_copy = Arc::clone(&list);
+            jhs.push(thread::spawn(move || {
+                // every thread inserts every key, and removes the odd ones
+                let mut removed = vec![];
+                for i in 0..KEYS {
+                    let key = (i * 7 + t * 13) % KEYS;
+                    list_copy.insert(key, key);
+                    if key % 2 == 1 {
+                        if let Some(entry) = list_copy.remove(&key) {
+                            removed.push(*entry);
+                        }
+                    }
+                }
+                removed
+            }));
+        }
+        let removed: Vec<u32> = jhs.into_iter().flat_map(|jh| jh.join().unwrap()).collect();
+        assert!(removed.iter().all(|key| key % 2 == 1));
+
+        let mut expected: Vec<(u32, u32)> = (0..KEYS).step_by(2).map(|i| (i, i)).collect();
+        for key in (1..KEYS).step_by(2) {
+            if list.contains_key(&key) {
+                expected.push((key, key));
+            }
+        }
+        expected.sort_unstable();
+        assert_eq!(entries(&list), expected);
+        assert_eq!(list.len(), expected.len());
+    }
+}
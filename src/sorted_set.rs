@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use crate::cell::{Borrowed, Cell};
+use crate::error::ListError;
+use crate::list::{Cursor, Iter, List};
+
+/// Lock-free ordered set: Valois's sorted linked list on top of [`List`].
+///
+/// Elements are kept in ascending order; every operation walks from the
+/// front of the list with [`Cursor::next`] and retries its single CAS
+/// from the current cursor position under contention.
+#[derive(Debug)]
+pub struct ConcurrentSortedSet<T> {
+    list: List<T>,
+}
+
+impl<T> Default for ConcurrentSortedSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ConcurrentSortedSet<T> {
+    pub fn new() -> Self {
+        Self { list: List::new() }
+    }
+
+    /// Iterates over the elements in ascending order; the iteration is
+    /// weakly consistent, see [`Iter`].
+    pub fn iter(&self) -> Iter<T> {
+        self.list.iter()
+    }
+}
+
+impl<T: Ord> ConcurrentSortedSet<T> {
+    /// Cursor at the first element not less than `key`.
+    fn seek(&self, key: &T) -> Cursor<T> {
+        let mut cursor = self.list.first().expect("list chain is corrupted");
+        cursor
+            .advance_while(|val| val < key)
+            .expect("list chain is corrupted");
+        cursor
+    }
+
+    /// Inserts `value`; returns `false` if an equal element is present.
+    pub fn insert(&self, value: T) -> bool {
+        fn key<T>(cell: &Arc<Cell<T>>) -> Borrowed<'_, T> {
+            cell.val().expect("detached cell is a data cell")
+        }
+
+        let mut cell = Cell::new_detached(value);
+
+        let mut cursor = self.seek(&key(&cell));
+        loop {
+            if cursor.val().as_deref() == Some(&*key(&cell)) {
+                return false;
+            }
+            match cursor.try_insert_cell(cell) {
+                Ok(()) => return true,
+                Err(err) if err.error() == ListError::NeedsUpdate => {
+                    cell = err.into_cell();
+                    cursor.update().expect("list chain is corrupted");
+                    cursor
+                        .advance_while(|val| val < &*key(&cell))
+                        .expect("list chain is corrupted");
+                }
+                Err(err) => panic!("list chain is corrupted: {}", err.error()),
+            }
+        }
+    }
+
+    pub fn contains(&self, key: &T) -> bool {
+        self.seek(key).val().as_deref() == Some(key)
+    }
+
+    /// Removes the element equal to `key` and returns it; see
+    /// [`List::pop_front`].
+    pub fn remove(&self, key: &T) -> Option<T> {
+        let mut cursor = self.seek(key);
+        loop {
+            if cursor.val().as_deref() != Some(key) {
+                return None;
+            }
+            match cursor.try_delete() {
+                Ok(removed) => return Some(removed.into_inner()),
+                Err(ListError::NeedsUpdate) => {
+                    cursor.update().expect("list chain is corrupted");
+                    cursor
+                        .advance_while(|val| val < key)
+                        .expect("list chain is corrupted");
+                }
+                Err(err) => panic!("list chain is corrupted: {}", err),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use super::ConcurrentSortedSet;
+
+    fn collect(set: &ConcurrentSortedSet<u32>) -> Vec<u32> {
+        set.iter().map(|val| *val).collect()
+    }
+
+    #[test]
+    fn test_insert_contains_remove() {
+        let set = ConcurrentSortedSet::new();
+
+        for i in [5, 1, 9, 3, 7, 1, 9] {
+            set.insert(i);
+        }
+        assert_eq!(collect(&set), vec![1, 3, 5, 7, 9]);
+        assert!(!set.insert(3));
+
+        assert!(set.contains(&7));
+        assert!(!set.contains(&8));
+
+        assert_eq!(set.remove(&7), Some(7));
+        assert!(set.remove(&7).is_none());
+        assert!(set.remove(&0).is_none());
+        assert_eq!(collect(&set), vec![1, 3, 5, 9]);
+    }
+
+    #[test]
+    fn test_concurrent_sorted_set() {
+        let set: Arc<ConcurrentSortedSet<u32>> = Arc::new(ConcurrentSortedSet::new());
+
+        const NUM_THREADS: usize = 16;
+        const KEYS: u32 = 200;
+
+        let mut jhs = vec![];
+        for t in 0..NUM_THREADS {
+            let set_copy = Arc::clone(&set);
+            jhs.push(thread::spawn(move || {
+                // every thread inserts every key, in a thread-specific order
+                let mut inserted = vec![];
+                for i in 0..KEYS {
+                    let key = (i * 7 + t as u32 * 13) % KEYS;
+                    if set_copy.insert(key) {
+                        inserted.push(key);
+                    }
+                }
+                inserted
+            }));
+        }
+        let mut inserted: Vec<u32> = jhs.into_iter().flat_map(|jh| jh.join().unwrap()).collect();
+        inserted.sort_unstable();
+        assert_eq!(inserted, (0..KEYS).collect::<Vec<_>>());
+        assert_eq!(collect(&set), (0..KEYS).collect::<Vec<_>>());
+
+        let mut jhs = vec![];
+        for t in 0..NUM_THREADS {
+            let set_copy = Arc::clone(&set);
+            jhs.push(thread::spawn(move || {
+                let mut removed = vec![];
+                for i in 0..KEYS {
+                    let key = (i * 11 + t as u32 * 17) % KEYS;
+                    assert!(!set_copy.contains(&(KEYS + key)));
+                    if let Some(val) = set_copy.remove(&key) {
+                        removed.push(val);
+                    }
+                }
+                removed
+            }));
+        }
+        let mut removed: Vec<u32> = jhs.into_iter().flat_map(|jh| jh.join().unwrap()).collect();
+        removed.sort_unstable();
+        assert_eq!(removed, (0..KEYS).collect::<Vec<_>>());
+        assert!(collect(&set).is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::task::Waker;
+use std::time::Instant;
+
+/// Threads parked and tasks pending until a lock-free container changes.
+///
+/// The condition waited for is read from atomics updated with `SeqCst`;
+/// notifiers check the number of waiters first and only take the lock
+/// when somebody is parked or registered, so the lock-free paths stay
+/// uncontended and never touch the wakers.
+#[derive(Debug, Default)]
+pub(crate) struct Waiters {
+    waiting: AtomicUsize,
+    wakers: Mutex<Vec<Waker>>,
+    cond: Condvar,
+}
+
+impl Waiters {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parks the thread while `blocked` returns `true`.
+    pub(crate) fn wait_while(&self, blocked: impl FnMut() -> bool) {
+        self.park(blocked, None);
+    }
+
+    /// Parks the thread while `blocked` returns `true`, until `deadline`;
+    /// returns `false` if the deadline passed first.
+    pub(crate) fn wait_while_until(&self, blocked: impl FnMut() -> bool, deadline: Instant) -> bool {
+        self.park(blocked, Some(deadline))
+    }
+
+    fn park(&self, mut blocked: impl FnMut() -> bool, deadline: Option<Instant>) -> bool {
+        let mut guard = self.lock();
+        self.waiting.fetch_add(1, Ordering::SeqCst);
+        let mut unblocked = true;
+        while blocked() {
+            guard = match deadline {
+                None => self.cond.wait(guard).unwrap_or_else(|err| err.into_inner()),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        unblocked = false;
+                        break;
+                    }
+                    let (guard, _) = self
+                        .cond
+                        .wait_timeout(guard, deadline - now)
+                        .unwrap_or_else(|err| err.into_inner());
+                    guard
+                }
+            };
+        }
+        self.waiting.fetch_sub(1, Ordering::SeqCst);
+        unblocked
+    }
+
+    /// Registers `waker` to be woken by the next notification if `blocked`
+    /// returns `true`; returns `false`, registering nothing, otherwise.
+    pub(crate) fn register(&self, waker: &Waker, blocked: impl FnOnce() -> bool) -> bool {
+        let mut wakers = self.lock();
+        // a task polled again before being woken is registered once
+        let known = wakers.iter().any(|known| known.will_wake(waker));
+        if !known {
+            wakers.push(waker.clone());
+            self.waiting.fetch_add(1, Ordering::SeqCst);
+        }
+        if blocked() {
+            return true;
+        }
+        if !known {
+            wakers.pop();
+            self.waiting.fetch_sub(1, Ordering::SeqCst);
+        }
+        false
+    }
+
+    /// Wakes all parked threads and registered tasks, if any, to recheck
+    /// their condition.
+    pub(crate) fn notify_all(&self) {
+        if self.waiting.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+        // a waiter between its check and parking holds the lock
+        let mut wakers = self.lock();
+        self.cond.notify_all();
+        let woken = std::mem::take(&mut *wakers);
+        self.waiting.fetch_sub(woken.len(), Ordering::SeqCst);
+        drop(wakers);
+        woken.into_iter().for_each(Waker::wake);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Waker>> {
+        self.wakers.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}